@@ -20,7 +20,12 @@ import {
 } from "@solana/spl-token";
 import { useWallet } from "@solana/wallet-adapter-react";
 import { useVestige } from "@/lib/use-vestige";
-import { VestigeClient, TOKEN_METADATA_PROGRAM_ID } from "@/lib/vestige-client";
+import {
+  VestigeClient,
+  TOKEN_METADATA_PROGRAM_ID,
+  TOKEN_PRECISION,
+  DEFAULT_LAUNCH_CONFIG,
+} from "@/lib/vestige-client";
 import { Loader2, ExternalLink, Copy } from "lucide-react";
 import toast from "react-hot-toast";
 
@@ -51,10 +56,11 @@ export default function CreateLaunchForm({
   });
   const [testMode, setTestMode] = useState(false);
 
-  // Derived from pMax
-  const pMinDisplay = formData.pMax
-    ? (parseFloat(formData.pMax) / 10).toString()
-    : "0";
+  // Derived from pMax: the program sets p_max = p_min * r_best
+  const pMinDisplay =
+    formData.pMax && formData.rBest
+      ? (parseFloat(formData.pMax) / parseInt(formData.rBest)).toString()
+      : "0";
 
   const applyTestMode = (enabled: boolean) => {
     setTestMode(enabled);
@@ -110,23 +116,26 @@ export default function CreateLaunchForm({
       const tokenSupply = new BN(tokenSupplyRaw);
       const bonusPool = new BN(bonusPoolRaw);
 
-      const pMaxLamports = VestigeClient.solToLamports(
-        parseFloat(formData.pMax),
-      );
-      const pMinLamports = VestigeClient.solToLamports(
-        parseFloat(formData.pMax) / 10,
-      );
-
       const rBest = new BN(parseInt(formData.rBest));
       const rMin = new BN(parseInt(formData.rMin));
       const graduationTarget = VestigeClient.solToLamports(
         parseFloat(formData.graduationTarget),
       );
 
+      // The program derives p_min = graduation_target * TOKEN_PRECISION / lp_reserve
+      // and p_max = p_min * r_best, so size the LP reserve from the starting price.
+      const pMinLamports = VestigeClient.solToLamports(
+        parseFloat(formData.pMax) / parseInt(formData.rBest),
+      );
+      const lpReserve = graduationTarget
+        .mul(new BN(TOKEN_PRECISION))
+        .div(pMinLamports);
+
       const program = (client as any).program;
       const [vaultPda] = VestigeClient.deriveVaultPda(launchPda);
       const [creatorFeeVaultPda] =
         VestigeClient.deriveCreatorFeeVaultPda(launchPda);
+      const [protocolConfigPda] = VestigeClient.deriveProtocolConfigPda();
 
       // Derive metadata PDA
       const [metadataPda] = PublicKey.findProgramAddressSync(
@@ -144,7 +153,7 @@ export default function CreateLaunchForm({
       //   2. initializeMint
       //   3. initializeLaunch (Anchor — creates Launch PDA + vault PDAs + metadata CPI)
       //   4. createAssociatedTokenAccount (launch vault ATA)
-      //   5. mintTo (supply + bonus directly to vault)
+      //   5. mintTo (supply + bonus + lp_reserve directly to vault)
       const lamports = await getMinimumBalanceForRentExemptMint(connection);
 
       const tokenName = formData.name || "Vestige Token";
@@ -155,16 +164,16 @@ export default function CreateLaunchForm({
         .initializeLaunch(
           tokenSupply,
           bonusPool,
+          lpReserve,
           startTime,
           endTime,
-          pMaxLamports,
-          pMinLamports,
           rBest,
           rMin,
           graduationTarget,
           tokenName,
           tokenSymbol,
           tokenUri,
+          DEFAULT_LAUNCH_CONFIG,
         )
         .accounts({
           launch: launchPda,
@@ -175,6 +184,7 @@ export default function CreateLaunchForm({
           tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
           creator: publicKey,
           systemProgram: SystemProgram.programId,
+          protocolConfig: protocolConfigPda,
         })
         .instruction();
 
@@ -185,7 +195,8 @@ export default function CreateLaunchForm({
         TOKEN_PROGRAM_ID,
         ASSOCIATED_TOKEN_PROGRAM_ID,
       );
-      const totalMint = tokenSupplyRaw + bonusPoolRaw;
+      const totalMint =
+        BigInt(tokenSupplyRaw) + BigInt(bonusPoolRaw) + BigInt(lpReserve.toString());
 
       const tx = new Transaction()
         .add(
@@ -465,7 +476,7 @@ export default function CreateLaunchForm({
           />
           <p className="text-xs text-[#6B7280] mt-1">
             Price drops from {formData.pMax || "?"} SOL to {pMinDisplay} SOL
-            (10:1 ratio)
+            (r_best:1 ratio)
           </p>
         </div>
 
@@ -16,6 +16,7 @@ export const LAUNCH_SEED = Buffer.from("launch");
 export const POSITION_SEED = Buffer.from("position");
 export const VAULT_SEED = Buffer.from("vault");
 export const CREATOR_FEE_VAULT_SEED = Buffer.from("creator_fee");
+export const PROTOCOL_CONFIG_SEED = Buffer.from("protocol_config");
 
 // Constants (matching on-chain)
 export const WEIGHT_PRECISION = 1_000;
@@ -35,6 +36,28 @@ export const PROTOCOL_TREASURY = new PublicKey(
   "GZctHpWXmsZC1YHACTGGcHhYxjdRqQvTpYkb3Jy9N2Ce",
 );
 
+// Launch options with every extension disabled: no vesting, caps, snipe
+// window, allowlist or bounty, SOL as the quote asset, leftovers burned.
+export const DEFAULT_LAUNCH_CONFIG = {
+  bonusVestCliff: new BN(0),
+  bonusVestDuration: new BN(0),
+  feeVestSchedule: [],
+  feeStreamDuration: new BN(0),
+  feeSplits: [],
+  maxSolPerWallet: new BN(0),
+  maxSupplyBpsPerWallet: 0,
+  snipeWindow: new BN(0),
+  snipeMaxBuy: new BN(0),
+  snipeFeeBps: 0,
+  allowlistRoot: new Array(32).fill(0),
+  publicStartTime: new BN(0),
+  attestationSigner: PublicKey.default,
+  leftoverPolicy: { burn: {} },
+  bonusClaimDeadline: new BN(0),
+  graduationBountyBps: 0,
+  graduationBountyCap: new BN(0),
+};
+
 // ============== Interfaces ==============
 
 export interface LaunchData {
@@ -139,6 +162,13 @@ export class VestigeClient {
     );
   }
 
+  static deriveProtocolConfigPda(): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+      [PROTOCOL_CONFIG_SEED],
+      PROGRAM_ID,
+    );
+  }
+
   // ============== Static Helpers ==============
 
   /** Convert a zero-padded byte array from on-chain to a trimmed string */
@@ -391,21 +421,22 @@ export class VestigeClient {
     creator: PublicKey,
     tokenSupply: BN,
     bonusPool: BN,
+    lpReserve: BN,
     startTime: BN,
     endTime: BN,
-    pMax: BN,
-    pMin: BN,
     rBest: BN,
     rMin: BN,
     graduationTarget: BN,
     name: string,
     symbol: string,
     uri: string,
+    config = DEFAULT_LAUNCH_CONFIG,
   ): Promise<string> {
     const [launchPda] = VestigeClient.deriveLaunchPda(creator, tokenMint);
     const [vaultPda] = VestigeClient.deriveVaultPda(launchPda);
     const [creatorFeeVaultPda] =
       VestigeClient.deriveCreatorFeeVaultPda(launchPda);
+    const [protocolConfigPda] = VestigeClient.deriveProtocolConfigPda();
 
     // Derive metadata PDA
     const [metadataPda] = PublicKey.findProgramAddressSync(
@@ -421,16 +452,16 @@ export class VestigeClient {
       .initializeLaunch(
         tokenSupply,
         bonusPool,
+        lpReserve,
         startTime,
         endTime,
-        pMax,
-        pMin,
         rBest,
         rMin,
         graduationTarget,
         name,
         symbol,
         uri,
+        config,
       )
       .accounts({
         launch: launchPda,
@@ -441,6 +472,7 @@ export class VestigeClient {
         tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
         creator,
         systemProgram: SystemProgram.programId,
+        protocolConfig: protocolConfigPda,
       })
       .rpc({ skipPreflight: true });
 
@@ -467,7 +499,7 @@ export class VestigeClient {
         ? solAmountLamports
         : new BN(Number(solAmountLamports));
     return this.program.methods
-      .buy(amount)
+      .buy(amount, null)
       .accounts({
         launch: launchPda,
         userPosition: positionPda,
@@ -498,7 +530,7 @@ export class VestigeClient {
       VestigeClient.deriveCreatorFeeVaultPda(launchPda);
 
     return this.program.methods
-      .buy(amount)
+      .buy(amount, null)
       .accounts({
         launch: launchPda,
         userPosition: positionPda,
@@ -543,10 +575,13 @@ export class VestigeClient {
   }
 
   async graduate(launchPda: PublicKey, authority: PublicKey): Promise<string> {
+    const [vaultPda] = VestigeClient.deriveVaultPda(launchPda);
+
     const tx = await this.program.methods
       .graduate()
       .accounts({
         launch: launchPda,
+        vault: vaultPda,
         authority,
       })
       .rpc({ skipPreflight: true });
@@ -579,7 +614,7 @@ export class VestigeClient {
 
   async creatorClaimFees(
     launchPda: PublicKey,
-    creator: PublicKey,
+    recipient: PublicKey,
   ): Promise<string> {
     const [creatorFeeVaultPda] =
       VestigeClient.deriveCreatorFeeVaultPda(launchPda);
@@ -589,7 +624,7 @@ export class VestigeClient {
       .accounts({
         launch: launchPda,
         creatorFeeVault: creatorFeeVaultPda,
-        creator,
+        recipient,
       })
       .rpc({ skipPreflight: true });
 
@@ -598,13 +633,13 @@ export class VestigeClient {
 
   async advanceMilestone(
     launchPda: PublicKey,
-    creator: PublicKey,
+    authority: PublicKey,
   ): Promise<string> {
     const tx = await this.program.methods
       .advanceMilestone()
       .accounts({
         launch: launchPda,
-        creator,
+        authority,
       })
       .rpc({ skipPreflight: true });
 
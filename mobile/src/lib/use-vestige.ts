@@ -346,7 +346,10 @@ export function useVestige() {
 
       if (!vaultInfo) throw new Error('Vault account not found');
 
-      const solForPool = new BN(vaultInfo.lamports - rentExemptMin);
+      const solForPool = VestigeClient.getPoolSolAmount(
+        launch,
+        new BN(vaultInfo.lamports - rentExemptMin)
+      );
       // tokensForPool is always lp_reserve — stored in launch state at init
       const tokensForPool = launch.lpReserve;
 
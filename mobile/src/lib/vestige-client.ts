@@ -13,6 +13,7 @@ export const LAUNCH_SEED = Buffer.from('launch');
 export const POSITION_SEED = Buffer.from('position');
 export const VAULT_SEED = Buffer.from('vault');
 export const CREATOR_FEE_VAULT_SEED = Buffer.from('creator_fee');
+export const PROTOCOL_CONFIG_SEED = Buffer.from('protocol_config');
 
 // Constants (matching on-chain)
 export const WEIGHT_PRECISION = 1_000;
@@ -31,6 +32,28 @@ export const PROTOCOL_TREASURY = new PublicKey(
   'GZctHpWXmsZC1YHACTGGcHhYxjdRqQvTpYkb3Jy9N2Ce'
 );
 
+// Launch options with every extension disabled: no vesting, caps, snipe
+// window, allowlist or bounty, SOL as the quote asset, leftovers burned.
+export const DEFAULT_LAUNCH_CONFIG = {
+  bonusVestCliff: new BN(0),
+  bonusVestDuration: new BN(0),
+  feeVestSchedule: [],
+  feeStreamDuration: new BN(0),
+  feeSplits: [],
+  maxSolPerWallet: new BN(0),
+  maxSupplyBpsPerWallet: 0,
+  snipeWindow: new BN(0),
+  snipeMaxBuy: new BN(0),
+  snipeFeeBps: 0,
+  allowlistRoot: new Array(32).fill(0),
+  publicStartTime: new BN(0),
+  attestationSigner: PublicKey.default,
+  leftoverPolicy: { burn: {} },
+  bonusClaimDeadline: new BN(0),
+  graduationBountyBps: 0,
+  graduationBountyCap: new BN(0),
+};
+
 // ============== Interfaces ==============
 
 export interface LaunchData {
@@ -63,7 +86,13 @@ export interface LaunchData {
   vaultBump: number;
   creatorFeeVaultBump: number;
   poolCreated: boolean;
-  lpReserve: BN; // tokens reserved for Raydium LP; p_min = graduationTarget * 1e9 / lpReserve
+  lpReserve: BN; // tokens seeded into the Raydium pool (after the fee and bounty shares)
+  lpReserveWithheld: BN; // p_min = graduationTarget * 1e9 / (lpReserve + lpReserveWithheld)
+  state: Record<string, object>; // e.g. { active: {} }
+  graduationFeeBps: number;
+  graduationBountyBps: number;
+  graduationBountyCap: BN;
+  graduationBountyPaid: BN;
 }
 
 export interface UserPositionData {
@@ -162,6 +191,13 @@ export class VestigeClient {
     );
   }
 
+  static deriveProtocolConfigPda(): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+      [PROTOCOL_CONFIG_SEED],
+      PROGRAM_ID
+    );
+  }
+
   // ============== Static Helpers ==============
 
   /** Convert a zero-padded byte array from on-chain to a trimmed string */
@@ -254,6 +290,33 @@ export class VestigeClient {
     return { solGross, protocolFee, creatorFee, solNet };
   }
 
+  /**
+   * SOL that graduate_to_dex releases for the pool, given the vault balance above rent
+   * before the transaction. Mirrors the on-chain release: a launch not yet paid its
+   * graduation bounty (Active, or Graduating after the buy that reached the target) pays
+   * it first, then the protocol graduation fee and any unpaid bounty
+   * share stay out of the pool.
+   */
+  static getPoolSolAmount(launch: LaunchData, vaultBalance: BN): BN {
+    const bps = (amount: BN, share: number) =>
+      amount.muln(share).divn(BPS_DENOMINATOR);
+    let bountyPaid = launch.graduationBountyPaid;
+    let balance = vaultBalance;
+    const listable = 'active' in launch.state || 'graduating' in launch.state;
+    if (listable && bountyPaid.isZero()) {
+      bountyPaid = bps(balance, launch.graduationBountyBps);
+      if (!launch.graduationBountyCap.isZero()) {
+        bountyPaid = BN.min(bountyPaid, launch.graduationBountyCap);
+      }
+      balance = balance.sub(bountyPaid);
+    }
+    const beforeBounty = balance.add(bountyPaid);
+    const graduationFee = bps(beforeBounty, launch.graduationFeeBps);
+    const bountyShare = bps(beforeBounty, launch.graduationBountyBps);
+    const bountyWithheld = BN.max(bountyShare.sub(bountyPaid), new BN(0));
+    return balance.sub(graduationFee).sub(bountyWithheld);
+  }
+
   // ============== Static Utils ==============
 
   static solToLamports(sol: number): BN {
@@ -24,6 +24,7 @@ import {
 import {
   VestigeClient,
   PROTOCOL_TREASURY,
+  DEFAULT_LAUNCH_CONFIG,
 } from './vestige-client';
 
 export const TOKEN_METADATA_PROGRAM_ID = new PublicKey(
@@ -212,7 +213,7 @@ export async function buildBuyTx(
   );
 
   const buyIx = await program.methods
-    .buy(solAmount)
+    .buy(solAmount, null)
     .accounts({
       launch: launchPda,
       userPosition: positionPda,
@@ -238,10 +239,13 @@ export async function buildGraduateTx(
   launchPda: PublicKey,
   authority: PublicKey
 ): Promise<Transaction> {
+  const [vaultPda] = VestigeClient.deriveVaultPda(launchPda);
+
   const tx = await program.methods
     .graduate()
     .accounts({
       launch: launchPda,
+      vault: vaultPda,
       authority,
     })
     .transaction();
@@ -278,7 +282,7 @@ export async function buildCreatorClaimFeesTx(
   program: any,
   connection: Connection,
   launchPda: PublicKey,
-  creator: PublicKey
+  recipient: PublicKey
 ): Promise<Transaction> {
   const [creatorFeeVaultPda] = VestigeClient.deriveCreatorFeeVaultPda(launchPda);
 
@@ -287,28 +291,28 @@ export async function buildCreatorClaimFeesTx(
     .accounts({
       launch: launchPda,
       creatorFeeVault: creatorFeeVaultPda,
-      creator,
+      recipient,
     })
     .transaction();
 
-  return setRecentBlockhash(connection, tx, creator);
+  return setRecentBlockhash(connection, tx, recipient);
 }
 
 export async function buildAdvanceMilestoneTx(
   program: any,
   connection: Connection,
   launchPda: PublicKey,
-  creator: PublicKey
+  authority: PublicKey
 ): Promise<Transaction> {
   const tx = await program.methods
     .advanceMilestone()
     .accounts({
       launch: launchPda,
-      creator,
+      authority,
     })
     .transaction();
 
-  return setRecentBlockhash(connection, tx, creator);
+  return setRecentBlockhash(connection, tx, authority);
 }
 
 /**
@@ -323,7 +327,7 @@ export async function buildAdvanceMilestoneTx(
  *   2. initializeMint
  *   3. initializeLaunch (Anchor — creates Launch PDA + vault PDAs)
  *   4. createAssociatedTokenAccount (launch vault ATA)
- *   5. mintTo (supply + bonus directly to vault)
+ *   5. mintTo (supply + bonus + lp_reserve directly to vault)
  *
  * Web frontend uses 2 separate transactions because each wallet.signTransaction()
  * call triggers a browser wallet popup. Mobile doesn't have that constraint.
@@ -344,11 +348,13 @@ export async function buildInitializeLaunchTx(
   name: string,
   symbol: string,
   uri: string,
+  config = DEFAULT_LAUNCH_CONFIG,
 ): Promise<Transaction> {
   const tokenMint = mintKeypair.publicKey;
   const [launchPda] = VestigeClient.deriveLaunchPda(creator, tokenMint);
   const [vaultPda] = VestigeClient.deriveVaultPda(launchPda);
   const [creatorFeeVaultPda] = VestigeClient.deriveCreatorFeeVaultPda(launchPda);
+  const [protocolConfigPda] = VestigeClient.deriveProtocolConfigPda();
 
   // Derive metadata PDA: ["metadata", metadata_program_id, mint]
   const [metadataPda] = PublicKey.findProgramAddressSync(
@@ -393,6 +399,7 @@ export async function buildInitializeLaunchTx(
       name,
       symbol,
       uri,
+      config,
     )
     .accounts({
       launch: launchPda,
@@ -403,6 +410,7 @@ export async function buildInitializeLaunchTx(
       tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
       creator,
       systemProgram: SystemProgram.programId,
+      protocolConfig: protocolConfigPda,
     })
     .instruction();
   tx.add(initLaunchIx);
@@ -429,14 +437,15 @@ export async function buildInitializeLaunchTx(
  *
  * Instruction sequence (all atomic — if any fails, all roll back):
  *   1. graduate_to_dex  — releases vault SOL to payer + pool tokens to payer ATA,
+ *                         pays the graduation fee to the protocol treasury,
  *                         marks is_graduated/pool_created on-chain
  *   2. SystemProgram.transfer(payer → payerWsolAta, solForPool)  — sends SOL to wSOL ATA
  *   3. SyncNative(payerWsolAta)  — wraps lamports to wSOL token balance
  *   4. Raydium CPMM initialize  — creates the pool using wSOL + token balances
  *
  * solForPool and tokensForPool are pre-computed by the caller from on-chain state:
- *   solForPool     = vaultLamports - rentExemptMin
- *   tokensForPool  = tokenVaultAmount - totalBonusReserved
+ *   solForPool     = VestigeClient.getPoolSolAmount(launch, vaultLamports - rentExemptMin)
+ *   tokensForPool  = launch.lpReserve
  */
 export async function buildGraduateToDexTx(
   program: any,
@@ -481,6 +490,7 @@ export async function buildGraduateToDexTx(
       payerTokenAccount: payerTokenAta,
       tokenProgram: TOKEN_PROGRAM_ID,
       systemProgram: SystemProgram.programId,
+      protocolTreasury: PROTOCOL_TREASURY,
     })
     .instruction();
   tx.add(graduateIx);
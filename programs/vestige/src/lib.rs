@@ -1,13 +1,20 @@
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
-use anchor_spl::token::{self, Token, TokenAccount, Mint, Transfer};
+use anchor_spl::token::{self, Token, TokenAccount, Mint, Transfer, InitializeAccount3, CloseAccount, Burn};
 use anchor_lang::solana_program::program::invoke;
 use anchor_lang::solana_program::instruction::{Instruction, AccountMeta};
+use solana_instructions_sysvar as ix_sysvar;
+use solana_sdk_ids::ed25519_program;
+use solana_sha256_hasher::hashv;
 use borsh::BorshSerialize;
 
 /// Metaplex Token Metadata program ID
 pub const TOKEN_METADATA_PROGRAM_ID: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
 
+/// Raydium CPMM program ID (devnet) and the AMM config index the clients create pools with
+pub const RAYDIUM_CPMM_PROGRAM_ID: Pubkey = pubkey!("DRaycpLY18LhpbydsBWbVJtxpNv9oXPgjRSfpF2bWpYb");
+pub const RAYDIUM_AMM_CONFIG_INDEX: u16 = 0;
+
 declare_id!("4RQMkiv5Lp4p862UeQxQs6YgWRPBud2fwLMR5GcSo1bf");
 
 // Seeds for PDAs
@@ -15,6 +22,8 @@ pub const LAUNCH_SEED: &[u8] = b"launch";
 pub const POSITION_SEED: &[u8] = b"position";
 pub const VAULT_SEED: &[u8] = b"vault";
 pub const CREATOR_FEE_VAULT_SEED: &[u8] = b"creator_fee";
+pub const PROTOCOL_CONFIG_SEED: &[u8] = b"protocol_config";
+pub const REFERRER_SEED: &[u8] = b"referrer";
 
 // Constants
 pub const WEIGHT_PRECISION: u128 = 1_000;
@@ -25,19 +34,59 @@ pub const PROTOCOL_FEE_BPS: u64 = 50;   // 0.5%
 pub const CREATOR_FEE_BPS: u64 = 50;    // 0.5%
 pub const BPS_DENOMINATOR: u64 = 10_000;
 
+// Referrers get at most half of the protocol fee
+pub const MAX_REFERRAL_SHARE_BPS: u64 = 5_000;
+
+// Protocol graduation fee: at most 5% of the vault at listing
+pub const MAX_GRADUATION_FEE_BPS: u64 = 500;
+
+// Anti-sniper protection bounds
+pub const MAX_SNIPE_WINDOW: i64 = 60 * 60;  // 1 hour after activation
+pub const MAX_SNIPE_FEE_BPS: u64 = 2_500;   // 25% extra fee at activation
+
+// Allowlist proofs are bounded so a presale buy always fits in one transaction
+pub const MAX_ALLOWLIST_PROOF_LEN: usize = 20;
+
+// Buyer attestation message: wallet (32) || launch (32) || expiry i64 LE (8) || max amount u64 LE (8)
+pub const ATTESTATION_MESSAGE_LEN: usize = 32 + 32 + 8 + 8;
+
 // Minimum initial buy (0.01 SOL)
 pub const MIN_INITIAL_BUY: u64 = 10_000_000;
 
-// Vesting milestone BPS (out of 10_000)
+// Default creator-fee vesting milestone BPS (out of 10_000), used when a launch
+// does not supply its own schedule
 pub const VEST_GRADUATION: u64 = 3000;  // 30%
 pub const VEST_M1: u64 = 2000;          // 20% (cumulative 50%)
 pub const VEST_M2: u64 = 2000;          // 20% (cumulative 70%)
 pub const VEST_M3: u64 = 3000;          // 30% (cumulative 100%)
 
+// Protocol bounds on custom creator-fee vesting schedules
+pub const MAX_FEE_VEST_STEPS: usize = 8;
+pub const MAX_FEE_VEST_DURATION: i64 = 2 * 365 * 24 * 60 * 60; // last step at most 2 years after graduation
+pub const MAX_FEE_VEST_AT_GRADUATION_BPS: u64 = VEST_GRADUATION; // at most 30% unlocked at graduation
+// Holder accounts are passed to advance_milestone, so the requirement must fit in one transaction.
+// Cranks source them from getTokenLargestAccounts (at most 20 accounts), two of which are the
+// launch's token vault and the pool vault, which never count.
+pub const MAX_MILESTONE_HOLDERS: u16 = 18;
+// A MinHolders holder counts only with at least 0.1% of token_supply, so a handful of
+// dust wallets cannot stand in for real holders
+pub const MIN_MILESTONE_HOLDER_BPS: u64 = 10;
+
+// Creator-fee split table
+pub const MAX_FEE_RECIPIENTS: usize = 5;
+
+// Graduation bounty: at most 1% of the vault goes to whoever graduates a launch
+pub const MAX_GRADUATION_BOUNTY_BPS: u64 = 100;
+
+// Leftover tokens returned to the creator vest over at most 2 years
+pub const MAX_LEFTOVER_VEST_DURATION: i64 = 2 * 365 * 24 * 60 * 60;
+// Buyers keep at least 30 days to claim a fully vested bonus before it can be swept
+pub const MIN_BONUS_CLAIM_WINDOW: i64 = 30 * 24 * 60 * 60;
+
 // Protocol treasury — replace with your actual wallet
 pub const PROTOCOL_TREASURY: Pubkey = pubkey!("GZctHpWXmsZC1YHACTGGcHhYxjdRqQvTpYkb3Jy9N2Ce");
 
-// Milestone time-lock interval (5 minutes for testing; use 7*24*60*60 for production)
+// Default milestone time-lock interval (5 minutes for testing; use 7*24*60*60 for production)
 pub const MILESTONE_INTERVAL: i64 = 5 * 60;
 
 // ============== Helper Functions ==============
@@ -46,7 +95,7 @@ pub const MILESTONE_INTERVAL: i64 = 5 * 60;
 /// Price decreases linearly as more tokens are purchased — early buyers pay the highest
 /// visual price but receive the largest bonus multiplier (risk weight), so their effective
 /// entry is rewarded. Late buyers pay lowest visual price with no bonus.
-fn get_curve_price(launch: &Launch, total_base_sold: u64) -> u64 {
+pub fn get_curve_price(launch: &Launch, total_base_sold: u64) -> u64 {
     if launch.token_supply == 0 {
         return launch.p_max;
     }
@@ -61,11 +110,16 @@ fn get_curve_price(launch: &Launch, total_base_sold: u64) -> u64 {
 /// Weight decays as SOL is raised, not as time passes — early buyers (when the
 /// curve is mostly empty and the visual price is highest) earn the most bonus.
 /// Returns weight * WEIGHT_PRECISION for fractional accuracy.
-fn get_risk_weight_scaled(launch: &Launch, _current_time: i64) -> u128 {
+pub fn get_risk_weight_scaled(launch: &Launch, _current_time: i64) -> u128 {
+    get_risk_weight_at(launch, launch.total_sol_collected)
+}
+
+/// Risk weight (scaled) the curve would have after `total_sol_collected` lamports are raised.
+pub fn get_risk_weight_at(launch: &Launch, total_sol_collected: u64) -> u128 {
     if launch.graduation_target == 0 {
         return (launch.r_best as u128) * WEIGHT_PRECISION;
     }
-    let collected = launch.total_sol_collected as u128;
+    let collected = total_sol_collected as u128;
     let target = launch.graduation_target as u128;
     let progress = collected.min(target);
     let weight_range = ((launch.r_best - launch.r_min) as u128) * WEIGHT_PRECISION;
@@ -74,8 +128,656 @@ fn get_risk_weight_scaled(launch: &Launch, _current_time: i64) -> u128 {
     best_scaled.saturating_sub(decrease)
 }
 
+/// Graduation progress in basis points (0..=10_000) for a given amount raised.
+pub fn get_graduation_progress_bps(launch: &Launch, total_sol_collected: u64) -> u64 {
+    if launch.graduation_target == 0 {
+        return BPS_DENOMINATOR;
+    }
+    let progress = (total_sol_collected.min(launch.graduation_target) as u128)
+        * (BPS_DENOMINATOR as u128)
+        / (launch.graduation_target as u128);
+    progress as u64
+}
+
+/// Derive (p_max, p_min) from the launch economics:
+///   p_min = graduation_target * TOKEN_PRECISION / lp_reserve  (= DEX opening price)
+///   p_max = p_min * r_best  (so early buyers' effective price == p_min after bonus)
+pub fn derive_prices(graduation_target: u64, lp_reserve: u64, r_best: u64) -> Result<(u64, u64)> {
+    let p_min = (graduation_target as u128)
+        .checked_mul(TOKEN_PRECISION)
+        .ok_or(VestigeError::Overflow)?
+        .checked_div(lp_reserve as u128)
+        .ok_or(VestigeError::InvalidLpReserve)? as u64;
+    let p_max = (p_min as u128)
+        .checked_mul(r_best as u128)
+        .ok_or(VestigeError::Overflow)? as u64;
+    require!(p_max > p_min, VestigeError::InvalidPriceRange);
+    Ok((p_max, p_min))
+}
+
+/// Tokens seeded into the pool when the graduation fee and bounty take `graduation_fee_bps`
+/// of the vault first: `lp_reserve * (1 - fee)`, so quote / tokens — the listing price —
+/// is unchanged.
+pub fn lp_reserve_after_fee(lp_reserve: u64, graduation_fee_bps: u64) -> Result<u64> {
+    let adjusted = (lp_reserve as u128)
+        .checked_mul(BPS_DENOMINATOR.saturating_sub(graduation_fee_bps) as u128).ok_or(VestigeError::Overflow)?
+        .checked_div(BPS_DENOMINATOR as u128).ok_or(VestigeError::Overflow)?;
+    Ok(adjusted as u64)
+}
+
+/// Graduation fee currently set in the ProtocolConfig PDA; 0 while it is uninitialized.
+fn protocol_graduation_fee_bps(protocol_config: &AccountInfo) -> Result<u64> {
+    if protocol_config.data_is_empty() {
+        return Ok(0);
+    }
+    let data = protocol_config.try_borrow_data()?;
+    Ok(ProtocolConfig::try_deserialize(&mut &data[..])?.graduation_fee_bps)
+}
+
+/// Worst-case bonus the launch can owe: the whole `token_supply` bought at `r_best`.
+/// `initialize_launch` requires the bonus pool to cover it; the cap in `compute_buy`
+/// is the backstop if the pool still runs dry.
+pub fn max_bonus_liability(token_supply: u64, r_best: u64) -> Result<u64> {
+    let best_scaled = (r_best as u128)
+        .checked_mul(WEIGHT_PRECISION)
+        .ok_or(VestigeError::Overflow)?;
+    calculate_bonus(token_supply, best_scaled)
+}
+
+/// Bonus tokens of `position` vested at `current_time`: nothing before
+/// `graduation_time + bonus_vest_cliff`, then linear from `graduation_time` over
+/// `bonus_vest_duration` (a zero duration releases everything at the cliff).
+pub fn vested_bonus(launch: &Launch, position: &UserPosition, current_time: i64) -> Result<u64> {
+    if !launch.state.is_settled_success() {
+        return Ok(0);
+    }
+    let elapsed = current_time.saturating_sub(launch.graduation_time);
+    if elapsed < launch.bonus_vest_cliff {
+        return Ok(0);
+    }
+    if elapsed >= launch.bonus_vest_duration {
+        return Ok(position.total_bonus_entitled);
+    }
+    let vested = (position.total_bonus_entitled as u128)
+        .checked_mul(elapsed as u128).ok_or(VestigeError::Overflow)?
+        .checked_div(launch.bonus_vest_duration as u128).ok_or(VestigeError::Overflow)?;
+    Ok(vested as u64)
+}
+
+/// Tokens in the token vault no buyer is owed once the curve has closed: unsold supply,
+/// unreserved bonus, bonus swept after the claim deadline, and the part of `lp_reserve`
+/// withheld from the pool to match the graduation fee and bounty.
+pub fn leftover_tokens(launch: &Launch) -> Result<u64> {
+    let leftover = launch.token_supply.saturating_sub(launch.total_base_sold)
+        .checked_add(launch.bonus_pool.saturating_sub(launch.total_bonus_reserved)).ok_or(VestigeError::Overflow)?
+        .checked_add(launch.bonus_swept).ok_or(VestigeError::Overflow)?
+        .checked_add(launch.lp_reserve_withheld).ok_or(VestigeError::Overflow)?;
+    Ok(leftover)
+}
+
+/// Whether the bonus claim window has closed at `current_time` (never without a deadline).
+pub fn bonus_claim_expired(launch: &Launch, current_time: i64) -> bool {
+    launch.state.is_settled_success()
+        && launch.bonus_claim_deadline > 0
+        && current_time >= launch.graduation_time.saturating_add(launch.bonus_claim_deadline)
+}
+
+/// Quote a holder gets back from one vault for returning `token_amount` base tokens to a
+/// failed launch: their pro-rata share of `vault_balance` across every base token still
+/// sold. The last tokens returned take whatever is left, so the vault empties exactly.
+pub fn failed_launch_refund(launch: &Launch, vault_balance: u64, token_amount: u64) -> Result<u64> {
+    require!(token_amount <= launch.total_base_sold, VestigeError::InsufficientTokens);
+    let refund = (vault_balance as u128)
+        .checked_mul(token_amount as u128).ok_or(VestigeError::Overflow)?
+        .checked_div(launch.total_base_sold as u128).ok_or(VestigeError::InvalidTokenAmount)?;
+    Ok(refund as u64)
+}
+
+/// Move `launch` to `to` (see `LaunchState`) and emit `LaunchStateChanged`.
+fn transition_launch(launch: &mut Launch, launch_key: Pubkey, to: LaunchState, timestamp: i64) -> Result<()> {
+    let from = launch.set_state(to, timestamp)?;
+    msg!("Launch state: {:?} -> {:?}", from, to);
+    emit!(LaunchStateChanged { launch: launch_key, from, to, timestamp });
+    Ok(())
+}
+
+/// Bounty owed to whoever graduates the launch: `graduation_bounty_bps` of the vault's
+/// releasable quote, capped at `graduation_bounty_cap` when one is set.
+pub fn graduation_bounty(launch: &Launch, vault_balance: u64) -> Result<u64> {
+    let bounty = (vault_balance as u128)
+        .checked_mul(launch.graduation_bounty_bps as u128).ok_or(VestigeError::Overflow)?
+        .checked_div(BPS_DENOMINATOR as u128).ok_or(VestigeError::Overflow)? as u64;
+    if launch.graduation_bounty_cap > 0 {
+        return Ok(bounty.min(launch.graduation_bounty_cap));
+    }
+    Ok(bounty)
+}
+
+/// Whether the graduation bounty is still owed: the launch has reached its target but
+/// not been listed, and no bounty was paid yet. A buy that reaches the target moves the
+/// launch to Graduating without paying one, so `graduate_to_dex` pays it then.
+pub fn graduation_bounty_owed(launch: &Launch) -> bool {
+    matches!(launch.state, LaunchState::Active | LaunchState::Graduating)
+        && launch.graduation_bounty_paid == 0
+}
+
+/// Split the vault's releasable quote at listing: the protocol graduation fee and the
+/// bounty share not yet paid out, both of the vault as it stood before any bounty was
+/// paid (lp_reserve was reduced by the same shares at launch, so the price holds), and
+/// the rest for the pool. Returns (graduation_fee, bounty_withheld, quote_for_pool).
+pub fn pool_release_amounts(launch: &Launch, vault_balance: u64) -> Result<(u64, u64, u64)> {
+    let vault_before_bounty = (vault_balance as u128)
+        .checked_add(launch.graduation_bounty_paid as u128).ok_or(VestigeError::Overflow)?;
+    let graduation_fee = vault_before_bounty
+        .checked_mul(launch.graduation_fee_bps as u128).ok_or(VestigeError::Overflow)?
+        .checked_div(BPS_DENOMINATOR as u128).ok_or(VestigeError::Overflow)? as u64;
+    // A bounty below its share (capped, skipped, or not owed) leaves the rest in the
+    // vault; it goes to `fee_authority` with the vault at `close_launch`
+    let bounty_share = vault_before_bounty
+        .checked_mul(launch.graduation_bounty_bps as u128).ok_or(VestigeError::Overflow)?
+        .checked_div(BPS_DENOMINATOR as u128).ok_or(VestigeError::Overflow)? as u64;
+    let bounty_withheld = bounty_share.saturating_sub(launch.graduation_bounty_paid);
+    let quote_for_pool = vault_balance
+        .checked_sub(graduation_fee).ok_or(VestigeError::Overflow)?
+        .checked_sub(bounty_withheld).ok_or(VestigeError::Overflow)?;
+    Ok((graduation_fee, bounty_withheld, quote_for_pool))
+}
+
+/// Tokens released for the pool at listing: `lp_reserve`, which guarantees the Raydium
+/// listing price equals p_min (the curve's endpoint price), plus any unreleased leftover
+/// under an AddToLp policy, listing below p_min by the same ratio.
+/// Returns (tokens_for_pool, lp_leftover).
+pub fn pool_token_amounts(launch: &Launch) -> Result<(u64, u64)> {
+    let lp_leftover = if launch.leftover_policy == LeftoverPolicy::AddToLp {
+        leftover_tokens(launch)?.saturating_sub(launch.leftover_released)
+    } else {
+        0
+    };
+    let tokens_for_pool = launch.lp_reserve
+        .checked_add(lp_leftover).ok_or(VestigeError::Overflow)?;
+    Ok((tokens_for_pool, lp_leftover))
+}
+
+/// Whether `graduate` would accept this launch at `current_time`.
+pub fn graduation_ready(launch: &Launch, current_time: i64) -> bool {
+    launch.state.is_trading()
+        && (launch.total_sol_collected >= launch.graduation_target || current_time > launch.end_time)
+}
+
+/// The schedule used when `LaunchConfig.fee_vest_schedule` is empty:
+/// 30% at graduation, then 20%, 20%, 30% one MILESTONE_INTERVAL apart.
+pub fn default_fee_vest_schedule() -> Vec<FeeVestStep> {
+    [VEST_GRADUATION, VEST_M1, VEST_M2, VEST_M3]
+        .iter()
+        .enumerate()
+        .map(|(i, bps)| FeeVestStep {
+            delay: MILESTONE_INTERVAL * i as i64,
+            bps: *bps as u16,
+            condition: MilestoneCondition::None,
+        })
+        .collect()
+}
+
+/// Check a creator-fee schedule against the protocol bounds: 1..=MAX_FEE_VEST_STEPS
+/// steps, non-decreasing delays within MAX_FEE_VEST_DURATION, shares summing to
+/// 100%, and no more than MAX_FEE_VEST_AT_GRADUATION_BPS unlocked at graduation.
+pub fn validate_fee_vest_schedule(steps: &[FeeVestStep]) -> Result<()> {
+    require!(
+        !steps.is_empty() && steps.len() <= MAX_FEE_VEST_STEPS,
+        VestigeError::InvalidFeeVestSchedule
+    );
+    let mut previous_delay = 0i64;
+    let mut total_bps = 0u64;
+    let mut at_graduation_bps = 0u64;
+    for step in steps {
+        require!(
+            step.bps > 0 && step.delay >= previous_delay && step.delay <= MAX_FEE_VEST_DURATION,
+            VestigeError::InvalidFeeVestSchedule
+        );
+        // Zero-delay steps unlock inside `graduate`, where no condition can be checked
+        match step.condition {
+            MilestoneCondition::None => {}
+            MilestoneCondition::MinPoolPriceBps(bps) => {
+                require!(step.delay > 0 && bps > 0, VestigeError::InvalidFeeVestSchedule)
+            }
+            MilestoneCondition::MinHolders(holders) => require!(
+                step.delay > 0 && holders > 0 && holders <= MAX_MILESTONE_HOLDERS,
+                VestigeError::InvalidFeeVestSchedule
+            ),
+        }
+        total_bps += step.bps as u64;
+        if step.delay == 0 {
+            at_graduation_bps += step.bps as u64;
+        }
+        previous_delay = step.delay;
+    }
+    require!(total_bps == BPS_DENOMINATOR, VestigeError::InvalidFeeVestSchedule);
+    require!(at_graduation_bps <= MAX_FEE_VEST_AT_GRADUATION_BPS, VestigeError::InvalidFeeVestSchedule);
+    Ok(())
+}
+
+/// Number of creator-fee milestones unlocked the moment the launch graduates
+/// (the leading steps with a zero delay).
+pub fn milestones_at_graduation(launch: &Launch) -> u8 {
+    launch.fee_vest_steps()
+        .iter()
+        .take_while(|step| step.delay == 0)
+        .count() as u8
+}
+
+/// Share of creator fees (in BPS) unlocked by the milestones reached so far.
+pub fn fee_unlocked_bps(launch: &Launch) -> u64 {
+    launch.fee_vest_steps()
+        .iter()
+        .take(launch.milestones_unlocked as usize)
+        .map(|step| step.bps as u64)
+        .sum()
+}
+
+/// Creator fees unlocked at `current_time`. Streaming launches unlock linearly
+/// from `graduation_time` over `fee_stream_duration`; the rest by milestones reached.
+pub fn creator_fees_unlocked(launch: &Launch, current_time: i64) -> Result<u64> {
+    if !launch.state.is_settled_success() {
+        return Ok(0);
+    }
+    let unlocked = if launch.fee_stream_duration > 0 {
+        let elapsed = current_time
+            .saturating_sub(launch.graduation_time)
+            .clamp(0, launch.fee_stream_duration);
+        (launch.total_creator_fees as u128)
+            .checked_mul(elapsed as u128).ok_or(VestigeError::Overflow)?
+            .checked_div(launch.fee_stream_duration as u128).ok_or(VestigeError::Overflow)?
+    } else {
+        (launch.total_creator_fees as u128)
+            .checked_mul(fee_unlocked_bps(launch) as u128).ok_or(VestigeError::Overflow)?
+            .checked_div(BPS_DENOMINATOR as u128).ok_or(VestigeError::Overflow)?
+    };
+    Ok(unlocked as u64)
+}
+
+/// Whether every creator fee has vested and each recipient has claimed its full share
+/// (split shares round down, so a split launch can leave dust in the fee vault).
+pub fn creator_fees_settled(launch: &Launch, current_time: i64) -> Result<bool> {
+    if creator_fees_unlocked(launch, current_time)? < launch.total_creator_fees {
+        return Ok(false);
+    }
+    if launch.fee_split_count == 0 {
+        return Ok(launch.creator_fees_claimed >= launch.total_creator_fees);
+    }
+    for (split, claimed) in launch.fee_splits().iter().zip(launch.fee_split_claimed) {
+        let share = (launch.total_creator_fees as u128)
+            .checked_mul(split.bps as u128).ok_or(VestigeError::Overflow)?
+            .checked_div(BPS_DENOMINATOR as u128).ok_or(VestigeError::Overflow)? as u64;
+        if claimed < share {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Whether `close_launch` may close the launch at `current_time`. A Graduated launch must
+/// have every bonus claimed or swept, leftover tokens vesting to the creator all released
+/// and every creator fee vested and claimed; a Failed one must have refunded every base
+/// token sold, so its vaults hold nothing anyone is owed.
+pub fn launch_settled(launch: &Launch, current_time: i64) -> Result<bool> {
+    match launch.state {
+        LaunchState::Graduated => {
+            let bonus_settled = launch.total_bonus_claimed
+                .checked_add(launch.bonus_swept).ok_or(VestigeError::Overflow)?;
+            if bonus_settled < launch.total_bonus_reserved {
+                return Ok(false);
+            }
+            if let LeftoverPolicy::VestToCreator(_) = launch.leftover_policy {
+                if launch.leftover_released < leftover_tokens(launch)? {
+                    return Ok(false);
+                }
+            }
+            creator_fees_settled(launch, current_time)
+        }
+        LaunchState::Failed => Ok(launch.total_base_sold == 0),
+        _ => Ok(false),
+    }
+}
+
+/// Whether `close_position` may close `position` while its launch still exists. A
+/// position left from an earlier launch closed at the same address always is. Otherwise
+/// base tokens must be gone from it unless the launch graduated (they are then the
+/// holder's for good); on a Failed launch they still count in `total_base_sold` until
+/// refunded. Its bonus must be fully claimed or forfeited (sold off, refunded, or past
+/// the bonus claim deadline).
+pub fn position_settled(launch: &Launch, position: &UserPosition, current_time: i64) -> bool {
+    if position.launch_slot != launch.created_slot {
+        return true;
+    }
+    (position.total_base_tokens == 0 || launch.state.is_settled_success())
+        && (position.total_bonus_entitled == 0
+            || position.has_claimed_bonus
+            || bonus_claim_expired(launch, current_time))
+}
+
+/// Check a creator-fee split table: at most MAX_FEE_RECIPIENTS distinct, non-default
+/// recipients with non-zero shares summing to 100%. An empty table is valid
+/// (the whole fee goes to `fee_authority`).
+pub fn validate_fee_splits(splits: &[FeeShare]) -> Result<()> {
+    if splits.is_empty() {
+        return Ok(());
+    }
+    require!(splits.len() <= MAX_FEE_RECIPIENTS, VestigeError::InvalidFeeSplits);
+    let mut total_bps = 0u64;
+    for (i, split) in splits.iter().enumerate() {
+        require!(
+            split.bps > 0
+                && split.recipient != Pubkey::default()
+                && !splits[..i].iter().any(|other| other.recipient == split.recipient),
+            VestigeError::InvalidFeeSplits
+        );
+        total_bps += split.bps as u64;
+    }
+    require!(total_bps == BPS_DENOMINATOR, VestigeError::InvalidFeeSplits);
+    Ok(())
+}
+
+/// Raydium CPMM vaults of the launch's token/quote pool: (token vault, quote vault).
+/// `quote_mint` is `Launch::pool_quote_mint` (WSOL for SOL launches).
+/// Derived from the canonical pool for RAYDIUM_AMM_CONFIG_INDEX, so a pool the
+/// creator spins up on another config cannot stand in for it.
+pub fn raydium_pool_vaults(token_mint: &Pubkey, quote_mint: &Pubkey) -> (Pubkey, Pubkey) {
+    let quote_mint = *quote_mint;
+    let (amm_config, _) = Pubkey::find_program_address(
+        &[b"amm_config", &RAYDIUM_AMM_CONFIG_INDEX.to_le_bytes()],
+        &RAYDIUM_CPMM_PROGRAM_ID,
+    );
+    let (mint_0, mint_1) = if quote_mint < *token_mint {
+        (quote_mint, *token_mint)
+    } else {
+        (*token_mint, quote_mint)
+    };
+    let (pool_state, _) = Pubkey::find_program_address(
+        &[b"pool", amm_config.as_ref(), mint_0.as_ref(), mint_1.as_ref()],
+        &RAYDIUM_CPMM_PROGRAM_ID,
+    );
+    let vault = |mint: &Pubkey| {
+        Pubkey::find_program_address(
+            &[b"pool_vault", pool_state.as_ref(), mint.as_ref()],
+            &RAYDIUM_CPMM_PROGRAM_ID,
+        ).0
+    };
+    (vault(token_mint), vault(&quote_mint))
+}
+
+/// Raydium CPMM authority PDA that owns every pool vault.
+pub fn raydium_vault_authority() -> Pubkey {
+    Pubkey::find_program_address(&[b"vault_and_lp_mint_auth_seed"], &RAYDIUM_CPMM_PROGRAM_ID).0
+}
+
+fn read_token_account(info: &AccountInfo) -> Result<TokenAccount> {
+    require_keys_eq!(*info.owner, token::ID, VestigeError::InvalidMilestoneAccounts);
+    let data = info.try_borrow_data()?;
+    TokenAccount::try_deserialize(&mut &data[..])
+}
+
+/// The account that holds or receives a launch's quote asset: `sol_account` for SOL
+/// launches, `token_account` (mint and owner checked by the context) for quote-mint launches.
+fn quote_account<'info>(
+    launch: &Launch,
+    sol_account: &AccountInfo<'info>,
+    token_account: Option<&Account<'info, TokenAccount>>,
+) -> Result<AccountInfo<'info>> {
+    if !launch.uses_quote_mint() {
+        return Ok(sol_account.clone());
+    }
+    token_account
+        .map(|account| account.to_account_info())
+        .ok_or_else(|| error!(VestigeError::MissingQuoteAccount))
+}
+
+/// Move `amount` of the quote asset from a signer's `from` account to `to`:
+/// a system transfer for SOL launches, a token transfer for quote-mint launches.
+fn pay_quote<'info>(
+    launch: &Launch,
+    from: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    to: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    if launch.uses_quote_mint() {
+        token::transfer(
+            CpiContext::new(
+                token_program.clone(),
+                Transfer { from: from.clone(), to: to.clone(), authority: authority.clone() },
+            ),
+            amount,
+        )
+    } else {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                system_program::Transfer { from: from.clone(), to: to.clone() },
+            ),
+            amount,
+        )
+    }
+}
+
+/// Release `amount` of the quote asset from a vault PDA (`vault` or `creator_fee_vault`)
+/// to `to`. SOL vaults are program-owned, so lamports move directly; quote-mint vaults
+/// are token accounts owned by the launch PDA, which signs.
+fn release_quote<'info>(
+    launch: &Launch,
+    launch_info: &AccountInfo<'info>,
+    vault: &AccountInfo<'info>,
+    to: &AccountInfo<'info>,
+    token_program: Option<&AccountInfo<'info>>,
+    amount: u64,
+) -> Result<()> {
+    if !launch.uses_quote_mint() {
+        **vault.try_borrow_mut_lamports()? -= amount;
+        **to.try_borrow_mut_lamports()? += amount;
+        return Ok(());
+    }
+    let token_program = token_program.ok_or(VestigeError::MissingQuoteAccount)?;
+    let seeds = &[LAUNCH_SEED, launch.creator.as_ref(), launch.token_mint.as_ref(), &[launch.bump]];
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program.clone(),
+            Transfer { from: vault.clone(), to: to.clone(), authority: launch_info.clone() },
+            &[&seeds[..]],
+        ),
+        amount,
+    )
+}
+
+/// Pay the graduation bounty from the vault to `caller` (its `caller_quote` token account
+/// on quote-mint launches; without one the bounty is skipped rather than blocking
+/// graduation). Returns the amount paid.
+fn pay_graduation_bounty<'info>(
+    launch: &Launch,
+    launch_info: &AccountInfo<'info>,
+    vault: &AccountInfo<'info>,
+    caller: &AccountInfo<'info>,
+    caller_quote: Option<&AccountInfo<'info>>,
+    token_program: Option<&AccountInfo<'info>>,
+) -> Result<u64> {
+    if launch.graduation_bounty_bps == 0 {
+        return Ok(0);
+    }
+    let bounty = graduation_bounty(launch, vault_quote_balance(launch, vault)?)?;
+    let to = match (launch.uses_quote_mint(), caller_quote) {
+        (false, _) => caller,
+        (true, Some(account)) => account,
+        (true, None) => return Ok(0),
+    };
+    if bounty > 0 {
+        release_quote(launch, launch_info, vault, to, token_program, bounty)?;
+    }
+    Ok(bounty)
+}
+
+/// Release the pool liquidity to whoever creates the Raydium pool later in the same
+/// transaction: the protocol graduation fee goes to the treasury, the bounty's share stays
+/// out of the pool, then the rest of the vault's quote goes to `quote_to` and `lp_reserve`
+/// (plus any AddToLp leftover) to `token_to`.
+/// Returns (graduation_fee, quote_released, tokens_released, lp_leftover).
+#[allow(clippy::too_many_arguments)]
+fn release_pool_liquidity<'info>(
+    launch: &Launch,
+    launch_info: &AccountInfo<'info>,
+    vault: &AccountInfo<'info>,
+    token_vault: &AccountInfo<'info>,
+    protocol_treasury: &AccountInfo<'info>,
+    treasury_quote: Option<&Account<'info, TokenAccount>>,
+    quote_to: &AccountInfo<'info>,
+    token_to: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+) -> Result<(u64, u64, u64, u64)> {
+    // Compute amounts (SOL vaults keep their rent-exempt minimum)
+    let (graduation_fee, _, sol_for_pool) = pool_release_amounts(launch, vault_quote_balance(launch, vault)?)?;
+    if graduation_fee > 0 {
+        let treasury = quote_account(launch, protocol_treasury, treasury_quote)?;
+        release_quote(launch, launch_info, vault, &treasury, Some(token_program), graduation_fee)?;
+    }
+
+    let (tokens_for_pool, lp_leftover) = pool_token_amounts(launch)?;
+
+    require!(sol_for_pool > 0, VestigeError::InsufficientPoolLiquidity);
+    require!(tokens_for_pool > 0, VestigeError::InsufficientPoolLiquidity);
+
+    // Transfer quote from vault (SOL moves as direct lamports — no subsequent CPI on
+    // the recipient needed)
+    release_quote(launch, launch_info, vault, quote_to, Some(token_program), sol_for_pool)?;
+
+    // Transfer tokens from token_vault (launch PDA signs)
+    let seeds = &[LAUNCH_SEED, launch.creator.as_ref(), launch.token_mint.as_ref(), &[launch.bump]];
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program.clone(),
+            Transfer { from: token_vault.clone(), to: token_to.clone(), authority: launch_info.clone() },
+            &[&seeds[..]],
+        ),
+        tokens_for_pool,
+    )?;
+
+    Ok((graduation_fee, sol_for_pool, tokens_for_pool, lp_leftover))
+}
+
+/// Close a vault PDA, refunding its rent to `destination`. SOL vaults hand over all
+/// their lamports; quote-mint vaults first sweep any token balance to `quote_destination`.
+fn close_vault<'info>(
+    launch: &Launch,
+    launch_info: &AccountInfo<'info>,
+    vault: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+    quote_destination: Option<&AccountInfo<'info>>,
+    token_program: &AccountInfo<'info>,
+) -> Result<()> {
+    if !launch.uses_quote_mint() {
+        let lamports = vault.lamports();
+        **vault.try_borrow_mut_lamports()? -= lamports;
+        **destination.try_borrow_mut_lamports()? += lamports;
+        return Ok(());
+    }
+    let balance = vault_quote_balance(launch, vault)?;
+    if balance > 0 {
+        let to = quote_destination.ok_or(VestigeError::MissingQuoteAccount)?;
+        release_quote(launch, launch_info, vault, to, Some(token_program), balance)?;
+    }
+    let seeds = &[LAUNCH_SEED, launch.creator.as_ref(), launch.token_mint.as_ref(), &[launch.bump]];
+    token::close_account(CpiContext::new_with_signer(
+        token_program.clone(),
+        CloseAccount { account: vault.clone(), destination: destination.clone(), authority: launch_info.clone() },
+        &[&seeds[..]],
+    ))
+}
+
+/// Quote a vault PDA can release: lamports above its rent-exempt minimum for SOL
+/// launches, the token balance for quote-mint launches.
+fn vault_quote_balance(launch: &Launch, vault: &AccountInfo) -> Result<u64> {
+    if launch.uses_quote_mint() {
+        let data = vault.try_borrow_data()?;
+        return Ok(TokenAccount::try_deserialize(&mut &data[..])?.amount);
+    }
+    let rent_exempt_min = Rent::get()?.minimum_balance(0);
+    Ok(vault.lamports().saturating_sub(rent_exempt_min))
+}
+
+/// Check a milestone's performance condition against the accounts passed to
+/// `advance_milestone` (its remaining accounts):
+///   MinPoolPriceBps — [pool token vault, pool quote vault] of the canonical Raydium pool;
+///                     spot price must be >= p_min * bps / 10_000
+///   MinHolders      — token accounts of the launch mint; distinct owners holding at least
+///                     MIN_MILESTONE_HOLDER_BPS of token_supply (excluding the launch and
+///                     the pool) must reach the minimum
+/// Both are deterrents against abandoned launches, not proofs of demand: the pool price is
+/// a spot reading of the reserves, which a swap earlier in the same transaction can move,
+/// and a creator able to fund enough wallets above the holding minimum can meet MinHolders.
+fn check_milestone_condition(
+    launch: &Launch,
+    launch_key: &Pubkey,
+    condition: MilestoneCondition,
+    accounts: &[AccountInfo],
+) -> Result<()> {
+    match condition {
+        MilestoneCondition::None => Ok(()),
+        MilestoneCondition::MinPoolPriceBps(bps) => {
+            let (token_vault_key, quote_vault_key) = raydium_pool_vaults(&launch.token_mint, &launch.pool_quote_mint());
+            require!(accounts.len() >= 2, VestigeError::InvalidMilestoneAccounts);
+            require_keys_eq!(accounts[0].key(), token_vault_key, VestigeError::InvalidMilestoneAccounts);
+            require_keys_eq!(accounts[1].key(), quote_vault_key, VestigeError::InvalidMilestoneAccounts);
+            let token_reserve = read_token_account(&accounts[0])?.amount;
+            let quote_reserve = read_token_account(&accounts[1])?.amount;
+            require!(token_reserve > 0, VestigeError::MilestoneConditionNotMet);
+
+            let pool_price = (quote_reserve as u128)
+                .checked_mul(TOKEN_PRECISION).ok_or(VestigeError::Overflow)?
+                .checked_div(token_reserve as u128).ok_or(VestigeError::Overflow)?;
+            let min_price = (launch.p_min as u128)
+                .checked_mul(bps as u128).ok_or(VestigeError::Overflow)?
+                .checked_div(BPS_DENOMINATOR as u128).ok_or(VestigeError::Overflow)?;
+            msg!("Pool price: {} (minimum {})", pool_price, min_price);
+            require!(pool_price >= min_price, VestigeError::MilestoneConditionNotMet);
+            Ok(())
+        }
+        MilestoneCondition::MinHolders(required) => {
+            let pool_authority = raydium_vault_authority();
+            let min_holding = (launch.token_supply as u128)
+                .checked_mul(MIN_MILESTONE_HOLDER_BPS as u128).ok_or(VestigeError::Overflow)?
+                / BPS_DENOMINATOR as u128;
+            let mut holders: Vec<Pubkey> = Vec::new();
+            for info in accounts {
+                let account = read_token_account(info)?;
+                require_keys_eq!(account.mint, launch.token_mint, VestigeError::InvalidMilestoneAccounts);
+                if account.amount == 0
+                    || (account.amount as u128) < min_holding
+                    || account.owner == *launch_key
+                    || account.owner == pool_authority
+                    || holders.contains(&account.owner)
+                {
+                    continue;
+                }
+                holders.push(account.owner);
+            }
+            msg!("Holders: {} (minimum {})", holders.len(), required);
+            require!(holders.len() >= required as usize, VestigeError::MilestoneConditionNotMet);
+            Ok(())
+        }
+    }
+}
+
+/// Unix time at which the next creator-fee milestone can be advanced, or `None`
+/// if the launch is not graduated or every milestone is already unlocked.
+/// Milestone N+1 requires graduation_time + fee_vest_schedule[N].delay.
+pub fn next_milestone_time(launch: &Launch) -> Option<i64> {
+    if !launch.state.is_settled_success() {
+        return None;
+    }
+    let step = launch.fee_vest_steps().get(launch.milestones_unlocked as usize)?;
+    launch.graduation_time.checked_add(step.delay)
+}
+
 /// base_tokens = sol_amount * TOKEN_PRECISION / curve_price
-fn calculate_base_tokens(sol_amount: u64, curve_price: u64) -> Result<u64> {
+pub fn calculate_base_tokens(sol_amount: u64, curve_price: u64) -> Result<u64> {
     require!(curve_price > 0, VestigeError::ZeroCurvePrice);
     let numerator = (sol_amount as u128)
         .checked_mul(TOKEN_PRECISION)
@@ -87,7 +789,7 @@ fn calculate_base_tokens(sol_amount: u64, curve_price: u64) -> Result<u64> {
 }
 
 /// bonus = base_tokens * (weight_scaled - WEIGHT_PRECISION) / WEIGHT_PRECISION
-fn calculate_bonus(base_tokens: u64, weight_scaled: u128) -> Result<u64> {
+pub fn calculate_bonus(base_tokens: u64, weight_scaled: u128) -> Result<u64> {
     if weight_scaled <= WEIGHT_PRECISION {
         return Ok(0);
     }
@@ -100,6 +802,271 @@ fn calculate_bonus(base_tokens: u64, weight_scaled: u128) -> Result<u64> {
     Ok(bonus as u64)
 }
 
+/// Split a gross SOL amount into (protocol_fee, creator_fee, net).
+fn split_fees(gross: u64) -> Result<(u64, u64, u64)> {
+    let protocol_fee = gross
+        .checked_mul(PROTOCOL_FEE_BPS).ok_or(VestigeError::Overflow)?
+        .checked_div(BPS_DENOMINATOR).ok_or(VestigeError::Overflow)?;
+    let creator_fee = gross
+        .checked_mul(CREATOR_FEE_BPS).ok_or(VestigeError::Overflow)?
+        .checked_div(BPS_DENOMINATOR).ok_or(VestigeError::Overflow)?;
+    let net = gross
+        .checked_sub(protocol_fee).ok_or(VestigeError::Overflow)?
+        .checked_sub(creator_fee).ok_or(VestigeError::Overflow)?;
+    Ok((protocol_fee, creator_fee, net))
+}
+
+/// Whether a buy at `current_time` falls within `snipe_window` seconds of activation
+/// (the creator's first buy).
+pub fn in_snipe_window(launch: &Launch, current_time: i64) -> bool {
+    launch.state == LaunchState::Active
+        && current_time.saturating_sub(launch.activation_time).max(0) < launch.snipe_window
+}
+
+/// Anti-sniper fee on a buy at `current_time`: within the snipe window buys pay an
+/// extra fee that starts at `snipe_fee_bps` and decays linearly to zero (and are
+/// capped at `snipe_max_buy`, checked by `compute_buy` on the filled amount).
+pub fn snipe_fee(launch: &Launch, sol_amount: u64, current_time: i64) -> Result<u64> {
+    if !in_snipe_window(launch, current_time) {
+        return Ok(0);
+    }
+    let elapsed = current_time.saturating_sub(launch.activation_time).max(0);
+    let remaining = (launch.snipe_window - elapsed) as u128;
+    let fee = (sol_amount as u128)
+        .checked_mul(launch.snipe_fee_bps as u128).ok_or(VestigeError::Overflow)?
+        .checked_mul(remaining).ok_or(VestigeError::Overflow)?
+        .checked_div(BPS_DENOMINATOR as u128 * launch.snipe_window as u128).ok_or(VestigeError::Overflow)?;
+    Ok(fee as u64)
+}
+
+/// Allowlist Merkle leaf for (wallet, max allocation in lamports).
+/// Leaves and nodes are domain-separated (0x00 / 0x01 prefix) so a node can never
+/// be passed off as a leaf.
+pub fn allowlist_leaf(wallet: &Pubkey, max_allocation: u64) -> [u8; 32] {
+    hashv(&[&[0x00][..], wallet.as_ref(), &max_allocation.to_le_bytes()]).to_bytes()
+}
+
+/// Parent of two allowlist nodes. Children are hashed in sorted order, so proofs
+/// carry no left/right flags.
+pub fn allowlist_node(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (lo, hi) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[&[0x01][..], lo, hi]).to_bytes()
+}
+
+/// Whether `proof` links `leaf` to `root`.
+pub fn verify_allowlist_proof(root: &[u8; 32], leaf: [u8; 32], proof: &[[u8; 32]]) -> bool {
+    let computed = proof.iter().fold(leaf, |node, sibling| allowlist_node(&node, sibling));
+    computed == *root
+}
+
+/// Whether buys at `current_time` fall in the allowlist (presale) window.
+pub fn in_presale(launch: &Launch, current_time: i64) -> bool {
+    launch.allowlist_root != [0u8; 32] && current_time < launch.public_start_time
+}
+
+/// Message an attestation signer signs to let `wallet` buy into `launch` until
+/// `expiry`, spending at most `max_amount` lamports in total.
+pub fn attestation_message(wallet: &Pubkey, launch: &Pubkey, expiry: i64, max_amount: u64) -> [u8; ATTESTATION_MESSAGE_LEN] {
+    let mut message = [0u8; ATTESTATION_MESSAGE_LEN];
+    message[..32].copy_from_slice(wallet.as_ref());
+    message[32..64].copy_from_slice(launch.as_ref());
+    message[64..72].copy_from_slice(&expiry.to_le_bytes());
+    message[72..].copy_from_slice(&max_amount.to_le_bytes());
+    message
+}
+
+/// Parse the data of an Ed25519 program instruction holding exactly one signature
+/// whose public key and message live in that same instruction.
+/// Returns (signer, message).
+pub fn parse_ed25519_instruction(data: &[u8]) -> Option<(Pubkey, &[u8])> {
+    // Header: num_signatures (u8) + padding (u8), then one 14-byte offsets record
+    const HEADER: usize = 2;
+    const OFFSETS: usize = 14;
+    if data.len() < HEADER + OFFSETS || data[0] != 1 {
+        return None;
+    }
+    let field = |i: usize| u16::from_le_bytes([data[HEADER + 2 * i], data[HEADER + 2 * i + 1]]);
+    let (signature_ix, pubkey_offset, pubkey_ix) = (field(1), field(2) as usize, field(3));
+    let (message_offset, message_len, message_ix) = (field(4) as usize, field(5) as usize, field(6));
+    // u16::MAX = "this instruction"; anything else could point the check at other data
+    if signature_ix != u16::MAX || pubkey_ix != u16::MAX || message_ix != u16::MAX {
+        return None;
+    }
+    let pubkey = data.get(pubkey_offset..pubkey_offset.checked_add(32)?)?;
+    let message = data.get(message_offset..message_offset.checked_add(message_len)?)?;
+    Some((Pubkey::try_from(pubkey).ok()?, message))
+}
+
+/// Check the Ed25519 instruction immediately before the current one: it must carry
+/// `launch.attestation_signer`'s signature over (buyer, launch, expiry, max amount),
+/// unexpired, with `total_spent` within the max amount.
+fn verify_attestation(
+    launch: &Launch,
+    launch_key: &Pubkey,
+    buyer: &Pubkey,
+    total_spent: u64,
+    instructions: &AccountInfo,
+    current_time: i64,
+) -> Result<()> {
+    let current_index = ix_sysvar::load_current_index_checked(instructions)?;
+    require!(current_index > 0, VestigeError::AttestationRequired);
+    let ix = ix_sysvar::load_instruction_at_checked(current_index as usize - 1, instructions)?;
+    require!(ix.program_id == ed25519_program::ID, VestigeError::AttestationRequired);
+
+    let (signer, message) = parse_ed25519_instruction(&ix.data)
+        .ok_or(VestigeError::InvalidAttestation)?;
+    require!(signer == launch.attestation_signer, VestigeError::InvalidAttestation);
+    require!(message.len() == ATTESTATION_MESSAGE_LEN, VestigeError::InvalidAttestation);
+    require!(
+        message[..32] == buyer.to_bytes()[..] && message[32..64] == launch_key.to_bytes()[..],
+        VestigeError::InvalidAttestation
+    );
+    let expiry = i64::from_le_bytes(message[64..72].try_into().unwrap());
+    let max_amount = u64::from_le_bytes(message[72..].try_into().unwrap());
+    require!(current_time <= expiry, VestigeError::AttestationExpired);
+    require!(total_spent <= max_amount, VestigeError::AttestationAmountExceeded);
+    Ok(())
+}
+
+/// Fees on a gross buy of `sol_amount`: (protocol_fee, creator_fee, snipe_fee, net).
+/// `creator_fee` includes the anti-sniper fee.
+fn buy_fees(launch: &Launch, sol_amount: u64, current_time: i64) -> Result<(u64, u64, u64, u64)> {
+    let (protocol_fee, base_creator_fee, net_after_fees) = split_fees(sol_amount)?;
+    let snipe_fee = snipe_fee(launch, sol_amount, current_time)?;
+    let creator_fee = base_creator_fee
+        .checked_add(snipe_fee).ok_or(VestigeError::Overflow)?;
+    let net_amount = net_after_fees
+        .checked_sub(snipe_fee).ok_or(VestigeError::Overflow)?;
+    Ok((protocol_fee, creator_fee, snipe_fee, net_amount))
+}
+
+/// Buy math shared by `buy` and `quote_buy` — no state is touched.
+/// Fees are taken from `sol_amount`; base tokens and bonus are priced on the net amount.
+/// Any anti-sniper fee is added to `creator_fee` (it goes to the creator fee vault).
+/// A buy that would sell past `token_supply` or raise past `graduation_target` is filled
+/// up to whichever cap comes first; the unfilled gross amount is `sol_refunded`.
+pub fn compute_buy(launch: &Launch, sol_amount: u64, current_time: i64) -> Result<BuyQuote> {
+    require!(sol_amount > 0, VestigeError::InvalidSolAmount);
+
+    // Price = f(supply already sold) — decreases as demand grows.
+    // Risk weight = f(SOL raised) — decreases toward graduation.
+    let curve_price = get_curve_price(launch, launch.total_base_sold);
+    require!(curve_price > 0, VestigeError::ZeroCurvePrice);
+
+    let remaining_supply = launch.token_supply.saturating_sub(launch.total_base_sold);
+    require!(remaining_supply > 0, VestigeError::TokenSupplyExceeded);
+    let remaining_target = launch.graduation_target.saturating_sub(launch.total_sol_collected);
+    require!(remaining_target > 0, VestigeError::GraduationTargetReached);
+
+    // Most net quote the curve still takes: the rest of the target, or enough to buy out the supply
+    let supply_net = (remaining_supply as u128)
+        .checked_mul(curve_price as u128).ok_or(VestigeError::Overflow)?
+        .div_ceil(TOKEN_PRECISION);
+    let net_cap = (remaining_target as u128).min(supply_net) as u64;
+
+    let (mut protocol_fee, mut creator_fee, mut snipe_fee, mut net_amount) =
+        buy_fees(launch, sol_amount, current_time)?;
+    let mut filled = sol_amount;
+    if net_amount > net_cap {
+        // Fees are proportional to the gross amount, so scale it down to the cap
+        filled = ((sol_amount as u128)
+            .checked_mul(net_cap as u128).ok_or(VestigeError::Overflow)?
+            .checked_div(net_amount as u128).ok_or(VestigeError::Overflow)?) as u64;
+        (protocol_fee, creator_fee, snipe_fee, net_amount) = buy_fees(launch, filled, current_time)?;
+        // Fee rounding can leave the fill a lamport or two off the cap: never past it,
+        // and as close below it as the gross amount allows
+        while net_amount > net_cap {
+            filled -= 1;
+            (protocol_fee, creator_fee, snipe_fee, net_amount) = buy_fees(launch, filled, current_time)?;
+        }
+        while filled < sol_amount {
+            let next = buy_fees(launch, filled + 1, current_time)?;
+            if next.3 > net_cap {
+                break;
+            }
+            filled += 1;
+            (protocol_fee, creator_fee, snipe_fee, net_amount) = next;
+        }
+    }
+    let sol_refunded = sol_amount - filled;
+
+    if launch.snipe_max_buy > 0 && in_snipe_window(launch, current_time) {
+        require!(filled <= launch.snipe_max_buy, VestigeError::SnipeBuyTooLarge);
+    }
+
+    let weight_scaled = get_risk_weight_scaled(launch, current_time);
+
+    // Calculate base tokens and bonus using net_amount (post-fee)
+    let base_tokens = calculate_base_tokens(net_amount, curve_price)?.min(remaining_supply);
+    require!(base_tokens > 0, VestigeError::ZeroBaseTokens);
+
+    // Late in a popular launch the bonus pool can run dry before the supply does —
+    // cap the bonus at what is left instead of reverting the whole buy.
+    let full_bonus = calculate_bonus(base_tokens, weight_scaled)?;
+    let remaining_bonus = launch.bonus_pool.saturating_sub(launch.total_bonus_reserved);
+    let bonus = full_bonus.min(remaining_bonus);
+    let bonus_shortfall = full_bonus - bonus;
+
+    let total_base_sold = launch.total_base_sold
+        .checked_add(base_tokens).ok_or(VestigeError::Overflow)?;
+    let total_sol_collected = launch.total_sol_collected
+        .checked_add(net_amount).ok_or(VestigeError::Overflow)?;
+
+    Ok(BuyQuote {
+        sol_amount: filled,
+        net_amount,
+        protocol_fee,
+        creator_fee,
+        snipe_fee,
+        base_tokens,
+        bonus,
+        bonus_shortfall,
+        curve_price,
+        price_after: get_curve_price(launch, total_base_sold),
+        weight_after_scaled: get_risk_weight_at(launch, total_sol_collected) as u64,
+        graduation_progress_bps: get_graduation_progress_bps(launch, total_sol_collected),
+        sol_refunded,
+    })
+}
+
+/// Sell math shared by `sell` and `quote_sell` — no state is touched.
+/// Tokens are bought back at the current curve price; fees are taken from the gross SOL.
+pub fn compute_sell(launch: &Launch, token_amount: u64) -> Result<SellQuote> {
+    require!(token_amount > 0, VestigeError::InvalidTokenAmount);
+
+    // Calculate SOL to return at current demand-based price
+    let curve_price = get_curve_price(launch, launch.total_base_sold);
+    require!(curve_price > 0, VestigeError::ZeroCurvePrice);
+
+    let sol_gross = (token_amount as u128)
+        .checked_mul(curve_price as u128)
+        .ok_or(VestigeError::Overflow)?
+        .checked_div(TOKEN_PRECISION)
+        .ok_or(VestigeError::Overflow)? as u64;
+
+    require!(sol_gross > 0, VestigeError::SellAmountTooSmall);
+
+    // Calculate fees from gross
+    let (protocol_fee, creator_fee, sol_net) = split_fees(sol_gross)?;
+
+    let total_base_sold = launch.total_base_sold
+        .checked_sub(token_amount).ok_or(VestigeError::Overflow)?;
+    let total_sol_collected = launch.total_sol_collected
+        .checked_sub(sol_gross).ok_or(VestigeError::Overflow)?;
+
+    Ok(SellQuote {
+        token_amount,
+        sol_gross,
+        protocol_fee,
+        creator_fee,
+        sol_net,
+        curve_price,
+        price_after: get_curve_price(launch, total_base_sold),
+        weight_after_scaled: get_risk_weight_at(launch, total_sol_collected) as u64,
+        graduation_progress_bps: get_graduation_progress_bps(launch, total_sol_collected),
+    })
+}
+
 /// Borsh-serializable types for Metaplex CreateMetadataAccountV3 CPI
 #[derive(BorshSerialize)]
 struct MetaplexCreator {
@@ -127,6 +1094,7 @@ struct CreateMetadataAccountV3Args {
 }
 
 /// Build a Metaplex CreateMetadataAccountV3 instruction manually
+#[allow(clippy::too_many_arguments)]
 fn build_create_metadata_v3_ix(
     metadata: Pubkey,
     mint: Pubkey,
@@ -198,15 +1166,16 @@ pub mod vestige {
     use super::*;
 
     /// Initialize a new launch with inverted bonding curve parameters.
-    /// Creates the Launch PDA and SOL vault PDA.
-    /// Also creates Metaplex token metadata via CPI.
-    /// Initialize a new launch.
+    /// Creates the Launch PDA and vault PDAs, and Metaplex token metadata via CPI.
     /// Prices are derived automatically from economic parameters:
     ///   p_min = graduation_target * TOKEN_PRECISION / lp_reserve  (= DEX opening price)
     ///   p_max = p_min * r_best                                    (= starting curve price)
     ///
-    /// This guarantees the curve's final price == Raydium listing price.
+    /// This guarantees the curve's final price == Raydium listing price. The graduation fee
+    /// and bounty shares of `lp_reserve` are withheld from the pool (`lp_reserve_after_fee`),
+    /// so `Launch.lp_reserve` stores the rest and `Launch.lp_reserve_withheld` the difference.
     /// Total minted = token_supply (tradeable) + bonus_pool + lp_reserve.
+    #[allow(clippy::too_many_arguments)]
     pub fn initialize_launch(
         ctx: Context<InitializeLaunch>,
         token_supply: u64,
@@ -220,6 +1189,7 @@ pub mod vestige {
         name: String,
         symbol: String,
         uri: String,
+        config: LaunchConfig,
     ) -> Result<()> {
         require!(end_time > start_time, VestigeError::InvalidTimeRange);
         require!(token_supply > 0, VestigeError::InvalidTokenSupply);
@@ -228,21 +1198,90 @@ pub mod vestige {
         require!(graduation_target > 0, VestigeError::InvalidGraduationTarget);
         require!(r_best > r_min, VestigeError::InvalidWeightRange);
         require!(r_min >= 1, VestigeError::WeightBelowMinimum);
+        require!(
+            bonus_pool >= max_bonus_liability(token_supply, r_best)?,
+            VestigeError::BonusPoolTooSmall
+        );
+        require!(
+            config.bonus_vest_cliff >= 0 && config.bonus_vest_duration >= 0,
+            VestigeError::InvalidVestingSchedule
+        );
+        // Creator fees either stream linearly or follow a milestone schedule, not both
+        let fee_vest_schedule = if config.fee_stream_duration > 0 {
+            require!(
+                config.fee_vest_schedule.is_empty() && config.fee_stream_duration <= MAX_FEE_VEST_DURATION,
+                VestigeError::InvalidFeeVestSchedule
+            );
+            Vec::new()
+        } else {
+            require!(config.fee_stream_duration == 0, VestigeError::InvalidFeeVestSchedule);
+            let schedule = if config.fee_vest_schedule.is_empty() {
+                default_fee_vest_schedule()
+            } else {
+                config.fee_vest_schedule.clone()
+            };
+            validate_fee_vest_schedule(&schedule)?;
+            schedule
+        };
 
-        // Derive prices from economics — this links the curve endpoint to the DEX listing price
-        // p_min = graduation_target * TOKEN_PRECISION / lp_reserve
-        let p_min = (graduation_target as u128)
-            .checked_mul(TOKEN_PRECISION)
-            .ok_or(VestigeError::Overflow)?
-            .checked_div(lp_reserve as u128)
-            .ok_or(VestigeError::InvalidLpReserve)? as u64;
-        // p_max = p_min * r_best (so early buyers' effective price == p_min after bonus)
-        let p_max = (p_min as u128)
-            .checked_mul(r_best as u128)
-            .ok_or(VestigeError::Overflow)? as u64;
-        require!(p_max > p_min, VestigeError::InvalidPriceRange);
-
-        // Create vault PDA (program-owned, holds lamports)
+        validate_fee_splits(&config.fee_splits)?;
+        require!(
+            config.max_supply_bps_per_wallet as u64 <= BPS_DENOMINATOR,
+            VestigeError::InvalidWalletCap
+        );
+        if let LeftoverPolicy::VestToCreator(duration) = config.leftover_policy {
+            require!(
+                duration > 0 && duration <= MAX_LEFTOVER_VEST_DURATION,
+                VestigeError::InvalidLeftoverPolicy
+            );
+        }
+        if config.bonus_claim_deadline != 0 {
+            let fully_vested = config.bonus_vest_cliff.max(config.bonus_vest_duration);
+            require!(
+                config.bonus_claim_deadline >= fully_vested.saturating_add(MIN_BONUS_CLAIM_WINDOW),
+                VestigeError::InvalidBonusClaimDeadline
+            );
+        }
+        require!(
+            config.graduation_bounty_bps as u64 <= MAX_GRADUATION_BOUNTY_BPS,
+            VestigeError::InvalidGraduationBounty
+        );
+        if config.allowlist_root != [0u8; 32] {
+            require!(
+                config.public_start_time > start_time && config.public_start_time < end_time,
+                VestigeError::InvalidAllowlistConfig
+            );
+        }
+        require!(
+            (0..=MAX_SNIPE_WINDOW).contains(&config.snipe_window)
+                && config.snipe_fee_bps as u64 <= MAX_SNIPE_FEE_BPS,
+            VestigeError::InvalidSnipeProtection
+        );
+
+        // Derive prices from economics — this links the curve endpoint to the DEX listing price:
+        // p_min = graduation_target / lp_reserve. The protocol graduation fee and the
+        // graduation bounty take their share of the vault before the pool is seeded, so the
+        // pool gets the same share less of lp_reserve and still lists at p_min; the withheld
+        // tokens join the leftover.
+        let (p_max, p_min) = derive_prices(graduation_target, lp_reserve, r_best)?;
+        let graduation_fee_bps = protocol_graduation_fee_bps(&ctx.accounts.protocol_config)?;
+        let pool_lp_reserve = lp_reserve_after_fee(
+            lp_reserve,
+            graduation_fee_bps + config.graduation_bounty_bps as u64,
+        )?;
+        require!(pool_lp_reserve > 0, VestigeError::InvalidLpReserve);
+
+        // With a quote mint, both vaults are token accounts of that mint owned by the
+        // launch PDA; otherwise they are program-owned and hold lamports
+        let (vault_space, vault_owner) = match &ctx.accounts.quote_mint {
+            Some(_) => {
+                require!(ctx.accounts.token_program.is_some(), VestigeError::MissingQuoteAccount);
+                (TokenAccount::LEN, token::ID)
+            }
+            None => (0, crate::ID),
+        };
+
+        // Create vault PDA (holds the quote raised on the curve)
         let vault = &ctx.accounts.vault;
         let launch_key = ctx.accounts.launch.key();
         let (_, vault_bump) = Pubkey::find_program_address(
@@ -250,7 +1289,7 @@ pub mod vestige {
             ctx.program_id,
         );
         let rent = Rent::get()?;
-        let lamports = rent.minimum_balance(0);
+        let lamports = rent.minimum_balance(vault_space);
         system_program::create_account(
             CpiContext::new_with_signer(
                 ctx.accounts.system_program.to_account_info(),
@@ -261,17 +1300,17 @@ pub mod vestige {
                 &[&[VAULT_SEED, launch_key.as_ref(), &[vault_bump]]],
             ),
             lamports,
-            0,
-            &crate::ID,
+            vault_space as u64,
+            &vault_owner,
         )?;
 
-        // Create creator_fee_vault PDA (program-owned, holds lamports for creator fees)
+        // Create creator_fee_vault PDA (holds creator fees)
         let creator_fee_vault = &ctx.accounts.creator_fee_vault;
         let (_, fee_vault_bump) = Pubkey::find_program_address(
             &[CREATOR_FEE_VAULT_SEED, launch_key.as_ref()],
             ctx.program_id,
         );
-        let fee_vault_lamports = rent.minimum_balance(0);
+        let fee_vault_lamports = rent.minimum_balance(vault_space);
         system_program::create_account(
             CpiContext::new_with_signer(
                 ctx.accounts.system_program.to_account_info(),
@@ -282,13 +1321,27 @@ pub mod vestige {
                 &[&[CREATOR_FEE_VAULT_SEED, launch_key.as_ref(), &[fee_vault_bump]]],
             ),
             fee_vault_lamports,
-            0,
-            &crate::ID,
+            vault_space as u64,
+            &vault_owner,
         )?;
 
+        if let (Some(quote_mint), Some(token_program)) = (&ctx.accounts.quote_mint, &ctx.accounts.token_program) {
+            for account in [vault, creator_fee_vault] {
+                token::initialize_account3(CpiContext::new(
+                    token_program.to_account_info(),
+                    InitializeAccount3 {
+                        account: account.to_account_info(),
+                        mint: quote_mint.to_account_info(),
+                        authority: ctx.accounts.launch.to_account_info(),
+                    },
+                ))?;
+            }
+        }
+
         // Initialize launch state
         let launch = &mut ctx.accounts.launch;
         launch.creator = ctx.accounts.creator.key();
+        launch.fee_authority = ctx.accounts.creator.key();
         launch.token_mint = ctx.accounts.token_mint.key();
         launch.token_supply = token_supply;
         launch.bonus_pool = bonus_pool;
@@ -316,7 +1369,35 @@ pub mod vestige {
         launch.vault_bump = vault_bump;
         launch.creator_fee_vault_bump = fee_vault_bump;
         launch.pool_created = false;
-        launch.lp_reserve = lp_reserve;
+        launch.state = LaunchState::Pending;
+        launch.created_slot = Clock::get()?.slot;
+        launch.lp_reserve = pool_lp_reserve;
+        launch.lp_reserve_withheld = lp_reserve - pool_lp_reserve;
+        launch.graduation_fee_bps = graduation_fee_bps as u16;
+        launch.bonus_vest_cliff = config.bonus_vest_cliff;
+        launch.bonus_vest_duration = config.bonus_vest_duration;
+        launch.fee_vest_step_count = fee_vest_schedule.len() as u8;
+        launch.fee_vest_schedule[..fee_vest_schedule.len()].copy_from_slice(&fee_vest_schedule);
+        launch.fee_stream_duration = config.fee_stream_duration;
+        launch.fee_split_count = config.fee_splits.len() as u8;
+        launch.fee_splits[..config.fee_splits.len()].copy_from_slice(&config.fee_splits);
+        launch.max_sol_per_wallet = config.max_sol_per_wallet;
+        launch.max_supply_bps_per_wallet = config.max_supply_bps_per_wallet;
+        launch.snipe_window = config.snipe_window;
+        launch.snipe_max_buy = config.snipe_max_buy;
+        launch.snipe_fee_bps = config.snipe_fee_bps;
+        launch.allowlist_root = config.allowlist_root;
+        launch.public_start_time = if config.allowlist_root != [0u8; 32] {
+            config.public_start_time
+        } else {
+            start_time
+        };
+        launch.attestation_signer = config.attestation_signer;
+        launch.quote_mint = ctx.accounts.quote_mint.as_ref().map(|m| m.key()).unwrap_or_default();
+        launch.leftover_policy = config.leftover_policy;
+        launch.bonus_claim_deadline = config.bonus_claim_deadline;
+        launch.graduation_bounty_bps = config.graduation_bounty_bps;
+        launch.graduation_bounty_cap = config.graduation_bounty_cap;
 
         // CPI to Metaplex to create token metadata
         // Manually construct the CreateMetadataAccountV3 instruction to avoid crate dependency conflicts
@@ -355,31 +1436,84 @@ pub mod vestige {
 
         msg!("Vestige Launch Initialized with Metadata!");
         msg!("Token: {} ({})", name, symbol);
-        msg!("Token Supply: {}, Bonus Pool: {}, LP Reserve: {}", token_supply, bonus_pool, lp_reserve);
+        msg!("Token Supply: {}, Bonus Pool: {}, LP Reserve: {}", token_supply, bonus_pool, pool_lp_reserve);
         msg!("Price: {} (start) -> {} (DEX listing) lamports", p_max, p_min);
         msg!("Risk Weight: {} -> {}", r_best, r_min);
         msg!("Graduation Target: {} lamports", graduation_target);
+        msg!("Bonus Vesting: cliff {}s, duration {}s", config.bonus_vest_cliff, config.bonus_vest_duration);
+
+        emit!(LaunchCreated {
+            launch: launch_key,
+            creator: ctx.accounts.creator.key(),
+            token_mint: ctx.accounts.token_mint.key(),
+            name,
+            symbol,
+            token_supply,
+            bonus_pool,
+            lp_reserve: pool_lp_reserve,
+            start_time,
+            end_time,
+            p_max,
+            p_min,
+            r_best,
+            r_min,
+            graduation_target,
+            bonus_vest_cliff: config.bonus_vest_cliff,
+            bonus_vest_duration: config.bonus_vest_duration,
+            fee_vest_schedule,
+            fee_stream_duration: config.fee_stream_duration,
+            fee_splits: config.fee_splits,
+            max_sol_per_wallet: config.max_sol_per_wallet,
+            max_supply_bps_per_wallet: config.max_supply_bps_per_wallet,
+            snipe_window: config.snipe_window,
+            snipe_max_buy: config.snipe_max_buy,
+            snipe_fee_bps: config.snipe_fee_bps,
+            allowlist_root: config.allowlist_root,
+            public_start_time: launch.public_start_time,
+            attestation_signer: config.attestation_signer,
+            quote_mint: launch.quote_mint,
+            leftover_policy: config.leftover_policy,
+            bonus_claim_deadline: config.bonus_claim_deadline,
+            graduation_bounty_bps: config.graduation_bounty_bps,
+            graduation_bounty_cap: config.graduation_bounty_cap,
+            graduation_fee_bps: graduation_fee_bps as u16,
+        });
 
         Ok(())
     }
 
     /// Buy tokens using SOL. Immediate token delivery of base tokens.
+    /// Between `start_time` and `public_start_time` of an allowlisted launch, `allowlist`
+    /// must prove (buyer, max allocation) against `allowlist_root`.
+    /// Launches with an `attestation_signer` also need that key's Ed25519 signature over
+    /// (buyer, launch, expiry, max amount) in the instruction right before this one.
     /// Bonus tokens are recorded and delivered at graduation.
     /// 1% total fee: 0.5% protocol treasury + 0.5% creator fee vault.
     /// Creator must make the first buy (min 0.01 SOL) to activate the launch.
-    pub fn buy(ctx: Context<Buy>, sol_amount: u64) -> Result<()> {
-        require!(sol_amount > 0, VestigeError::InvalidSolAmount);
-
+    /// A buy that hits the supply or graduation-target cap is partially filled and only
+    /// the filled amount is charged; the fill is returned (as with `quote_buy`) and emitted.
+    /// The buy that reaches `graduation_target` graduates the launch, closing trading. If it
+    /// passes `pool_token_account` it also releases the pool liquidity to the buyer, who
+    /// creates the Raydium pool later in the same transaction (as with `graduate_to_dex`).
+    pub fn buy(ctx: Context<Buy>, sol_amount: u64, allowlist: Option<AllowlistProof>) -> Result<BuyQuote> {
         let launch = &ctx.accounts.launch;
         let clock = Clock::get()?;
 
         require!(clock.unix_timestamp >= launch.start_time, VestigeError::LaunchNotStarted);
-        require!(!launch.is_graduated, VestigeError::AlreadyGraduated);
+        require!(launch.state.is_trading(), VestigeError::AlreadyGraduated);
+
+        // Positions share seeds with any launch closed earlier at this address; only one
+        // opened on this launch (or just created) is its own
+        let position = &mut ctx.accounts.user_position;
+        if position.user == Pubkey::default() {
+            position.launch_slot = launch.created_slot;
+        }
+        require!(position.launch_slot == launch.created_slot, VestigeError::StalePosition);
 
-        // Initial buy check: creator must buy first
-        if !launch.has_initial_buy {
+        // Initial buy check: creator (current fee_authority) must buy first
+        if launch.state == LaunchState::Pending {
             require!(
-                ctx.accounts.user.key() == launch.creator,
+                ctx.accounts.user.key() == launch.fee_authority,
                 VestigeError::CreatorMustBuyFirst
             );
             require!(
@@ -388,75 +1522,85 @@ pub mod vestige {
             );
         }
 
-        // Calculate fees
-        let protocol_fee = sol_amount
-            .checked_mul(PROTOCOL_FEE_BPS).ok_or(VestigeError::Overflow)?
-            .checked_div(BPS_DENOMINATOR).ok_or(VestigeError::Overflow)?;
-        let creator_fee = sol_amount
-            .checked_mul(CREATOR_FEE_BPS).ok_or(VestigeError::Overflow)?
-            .checked_div(BPS_DENOMINATOR).ok_or(VestigeError::Overflow)?;
-        let net_amount = sol_amount
-            .checked_sub(protocol_fee).ok_or(VestigeError::Overflow)?
-            .checked_sub(creator_fee).ok_or(VestigeError::Overflow)?;
-
-        // Price = f(supply already sold) — increases as demand grows.
-        // Risk weight = f(time) — decreases over launch window (rewards early buyers with bonus).
-        let curve_price = get_curve_price(launch, launch.total_base_sold);
-        require!(curve_price > 0, VestigeError::ZeroCurvePrice);
+        let quote = compute_buy(launch, sol_amount, clock.unix_timestamp)?;
+        let BuyQuote { sol_amount, protocol_fee, creator_fee, snipe_fee, net_amount, base_tokens, bonus, bonus_shortfall, curve_price, sol_refunded, .. } = quote.clone();
+        launch.check_wallet_caps(&ctx.accounts.user_position, &quote)?;
 
-        let weight_scaled = get_risk_weight_scaled(launch, clock.unix_timestamp);
+        // Presale window: only allowlisted wallets, up to their allocation.
+        // The creator's activating buy is exempt.
+        if launch.state == LaunchState::Active && in_presale(launch, clock.unix_timestamp) {
+            let allowlist = allowlist.ok_or(VestigeError::AllowlistProofRequired)?;
+            require!(allowlist.proof.len() <= MAX_ALLOWLIST_PROOF_LEN, VestigeError::InvalidAllowlistProof);
+            let leaf = allowlist_leaf(&ctx.accounts.user.key(), allowlist.max_allocation);
+            require!(
+                verify_allowlist_proof(&launch.allowlist_root, leaf, &allowlist.proof),
+                VestigeError::InvalidAllowlistProof
+            );
+            let sol_spent = ctx.accounts.user_position.total_sol_spent
+                .checked_add(sol_amount).ok_or(VestigeError::Overflow)?;
+            require!(sol_spent <= allowlist.max_allocation, VestigeError::AllowlistAllocationExceeded);
+        }
 
-        // Calculate base tokens and bonus using net_amount (post-fee)
-        let base_tokens = calculate_base_tokens(net_amount, curve_price)?;
-        require!(base_tokens > 0, VestigeError::ZeroBaseTokens);
+        // Off-chain attestation (captcha, KYC, ...). The creator's activating buy is exempt.
+        if launch.state == LaunchState::Active && launch.attestation_signer != Pubkey::default() {
+            let instructions = ctx.accounts.instructions.as_ref()
+                .ok_or(VestigeError::AttestationRequired)?;
+            let sol_spent = ctx.accounts.user_position.total_sol_spent
+                .checked_add(sol_amount).ok_or(VestigeError::Overflow)?;
+            verify_attestation(
+                launch,
+                &launch.key(),
+                &ctx.accounts.user.key(),
+                sol_spent,
+                instructions,
+                clock.unix_timestamp,
+            )?;
+        }
 
-        let bonus = calculate_bonus(base_tokens, weight_scaled)?;
+        // Referral share of the protocol fee (needs both the referrer and the protocol config)
+        let referral_fee = match (&ctx.accounts.referrer, &ctx.accounts.protocol_config) {
+            (Some(referrer), Some(config)) => {
+                require!(referrer.authority != ctx.accounts.user.key(), VestigeError::SelfReferral);
+                protocol_fee
+                    .checked_mul(config.referral_share_bps).ok_or(VestigeError::Overflow)?
+                    .checked_div(BPS_DENOMINATOR).ok_or(VestigeError::Overflow)?
+            }
+            (Some(_), None) => return err!(VestigeError::MissingProtocolConfig),
+            _ => 0,
+        };
+        let treasury_fee = protocol_fee - referral_fee;
 
-        // Check supply limits
-        require!(
-            launch.total_base_sold.checked_add(base_tokens).ok_or(VestigeError::Overflow)? <= launch.token_supply,
-            VestigeError::TokenSupplyExceeded
-        );
-        require!(
-            launch.total_bonus_reserved.checked_add(bonus).ok_or(VestigeError::Overflow)? <= launch.bonus_pool,
-            VestigeError::BonusPoolExceeded
-        );
+        // Quote moves as lamports from the wallet, or as tokens from its quote token account
+        let user_info = ctx.accounts.user.to_account_info();
+        let user_quote = quote_account(launch, &user_info, ctx.accounts.user_quote_account.as_ref())?;
+        let system_info = ctx.accounts.system_program.to_account_info();
+        let token_info = ctx.accounts.token_program.to_account_info();
 
         // Transfer protocol fee to treasury
-        system_program::transfer(
-            CpiContext::new(
-                ctx.accounts.system_program.to_account_info(),
-                system_program::Transfer {
-                    from: ctx.accounts.user.to_account_info(),
-                    to: ctx.accounts.protocol_treasury.to_account_info(),
-                },
-            ),
-            protocol_fee,
-        )?;
+        let treasury = quote_account(launch, &ctx.accounts.protocol_treasury, ctx.accounts.treasury_quote_account.as_ref())?;
+        pay_quote(launch, &user_quote, &user_info, &treasury, &system_info, &token_info, treasury_fee)?;
+
+        // Transfer referral fee: SOL accrues in the referrer PDA until claimed, a quote
+        // token goes straight to the referrer's token account
+        if let Some(referrer) = ctx.accounts.referrer.as_mut() {
+            if launch.uses_quote_mint() {
+                let referrer_quote = ctx.accounts.referrer_quote_account.as_ref()
+                    .ok_or(VestigeError::MissingQuoteAccount)?;
+                require_keys_eq!(referrer_quote.owner, referrer.authority, VestigeError::InvalidQuoteAccount);
+                pay_quote(launch, &user_quote, &user_info, &referrer_quote.to_account_info(), &system_info, &token_info, referral_fee)?;
+            } else if referral_fee > 0 {
+                pay_quote(launch, &user_quote, &user_info, &referrer.to_account_info(), &system_info, &token_info, referral_fee)?;
+            }
+            referrer.record_referral(referral_fee, sol_amount, launch.uses_quote_mint())?;
+        }
 
         // Transfer creator fee to creator_fee_vault PDA
-        system_program::transfer(
-            CpiContext::new(
-                ctx.accounts.system_program.to_account_info(),
-                system_program::Transfer {
-                    from: ctx.accounts.user.to_account_info(),
-                    to: ctx.accounts.creator_fee_vault.to_account_info(),
-                },
-            ),
-            creator_fee,
-        )?;
+        let creator_fee_vault = ctx.accounts.creator_fee_vault.to_account_info();
+        pay_quote(launch, &user_quote, &user_info, &creator_fee_vault, &system_info, &token_info, creator_fee)?;
 
         // Transfer net_amount to vault (for liquidity)
-        system_program::transfer(
-            CpiContext::new(
-                ctx.accounts.system_program.to_account_info(),
-                system_program::Transfer {
-                    from: ctx.accounts.user.to_account_info(),
-                    to: ctx.accounts.vault.to_account_info(),
-                },
-            ),
-            net_amount,
-        )?;
+        let vault = ctx.accounts.vault.to_account_info();
+        pay_quote(launch, &user_quote, &user_info, &vault, &system_info, &token_info, net_amount)?;
 
         // Transfer base_tokens from token_vault to user ATA (Launch PDA signs)
         let seeds = &[
@@ -486,80 +1630,131 @@ pub mod vestige {
 
         position.user = ctx.accounts.user.key();
         position.launch = ctx.accounts.launch.key();
-        position.total_sol_spent = position.total_sol_spent
-            .checked_add(sol_amount).ok_or(VestigeError::Overflow)?;
-        position.total_base_tokens = position.total_base_tokens
-            .checked_add(base_tokens).ok_or(VestigeError::Overflow)?;
-        position.total_bonus_entitled = position.total_bonus_entitled
-            .checked_add(bonus).ok_or(VestigeError::Overflow)?;
+        position.record_buy(&quote)?;
         position.bump = ctx.bumps.user_position;
 
-        // Update launch totals
+        // Update launch totals; the creator's first buy activates the launch
+        let launch_key = ctx.accounts.launch.key();
         let launch = &mut ctx.accounts.launch;
-        launch.total_base_sold = launch.total_base_sold
-            .checked_add(base_tokens).ok_or(VestigeError::Overflow)?;
-        launch.total_bonus_reserved = launch.total_bonus_reserved
-            .checked_add(bonus).ok_or(VestigeError::Overflow)?;
-        launch.total_sol_collected = launch.total_sol_collected
-            .checked_add(net_amount).ok_or(VestigeError::Overflow)?;
-        launch.total_creator_fees = launch.total_creator_fees
-            .checked_add(creator_fee).ok_or(VestigeError::Overflow)?;
-        if is_new {
-            launch.total_participants = launch.total_participants
-                .checked_add(1).ok_or(VestigeError::Overflow)?;
-        }
-        if !launch.has_initial_buy {
-            launch.has_initial_buy = true;
+        if launch.state == LaunchState::Pending {
+            launch.activation_time = clock.unix_timestamp;
+            transition_launch(launch, launch_key, LaunchState::Active, clock.unix_timestamp)?;
         }
+        launch.record_buy(&quote, is_new)?;
+
+        // Reaching the target graduates the launch below — no buy or sell lands after it
+        let graduated = launch.total_sol_collected >= launch.graduation_target;
 
         msg!("Buy: {} lamports (net {} after fees) -> {} base tokens + {} bonus entitled", sol_amount, net_amount, base_tokens, bonus);
+        if sol_refunded > 0 {
+            msg!("Partial fill: {} lamports not charged (supply or graduation target reached)", sol_refunded);
+        }
+        if bonus_shortfall > 0 {
+            msg!("Bonus pool nearly exhausted: bonus reduced by {}", bonus_shortfall);
+        }
+        if snipe_fee > 0 {
+            msg!("Anti-sniper fee: {} lamports to creator fee vault", snipe_fee);
+        }
 
-        Ok(())
+        let position = &ctx.accounts.user_position;
+        emit!(BuyEvent {
+            launch: launch.key(),
+            user: position.user,
+            sol_amount,
+            sol_refunded,
+            net_amount,
+            protocol_fee,
+            creator_fee,
+            snipe_fee,
+            referrer: ctx.accounts.referrer.as_ref().map(|r| r.authority).unwrap_or_default(),
+            referral_fee,
+            base_tokens,
+            bonus,
+            bonus_shortfall,
+            curve_price,
+            position_base_tokens: position.total_base_tokens,
+            position_bonus_entitled: position.total_bonus_entitled,
+            total_base_sold: launch.total_base_sold,
+            total_sol_collected: launch.total_sol_collected,
+            timestamp: clock.unix_timestamp,
+        });
+
+        if graduated {
+            let (graduation_fee, sol_released, tokens_released) = match ctx.accounts.pool_token_account.as_ref() {
+                Some(pool_token_account) => {
+                    let launch = &ctx.accounts.launch;
+                    let (graduation_fee, sol_released, tokens_released, lp_leftover) = release_pool_liquidity(
+                        launch,
+                        &launch.to_account_info(),
+                        &vault,
+                        &ctx.accounts.token_vault.to_account_info(),
+                        &ctx.accounts.protocol_treasury,
+                        ctx.accounts.treasury_quote_account.as_ref(),
+                        &user_quote,
+                        &pool_token_account.to_account_info(),
+                        &token_info,
+                    )?;
+                    let launch = &mut ctx.accounts.launch;
+                    launch.leftover_released = launch.leftover_released
+                        .checked_add(lp_leftover).ok_or(VestigeError::Overflow)?;
+                    (graduation_fee, sol_released, tokens_released)
+                }
+                None => (0, 0, 0),
+            };
+
+            // Without the pool accounts the launch waits in Graduating for `graduate_to_dex`
+            let pool_created = ctx.accounts.pool_token_account.is_some();
+            let launch = &mut ctx.accounts.launch;
+            let to = if pool_created { LaunchState::Graduated } else { LaunchState::Graduating };
+            transition_launch(launch, launch_key, to, clock.unix_timestamp)?;
+
+            msg!("=== LAUNCH GRADUATED ===");
+            msg!("Graduation target reached by this buy");
+            if pool_created {
+                msg!("SOL released: {} lamports", sol_released);
+                msg!("Tokens released: {}", tokens_released);
+            }
+            if graduation_fee > 0 {
+                msg!("Graduation fee: {} to protocol treasury", graduation_fee);
+            }
+
+            emit!(GraduationEvent {
+                launch: launch.key(),
+                total_sol_collected: launch.total_sol_collected,
+                total_base_sold: launch.total_base_sold,
+                total_bonus_reserved: launch.total_bonus_reserved,
+                sol_released,
+                tokens_released,
+                pool_created,
+                graduation_fee,
+                bounty: 0,
+                bounty_recipient: Pubkey::default(),
+                milestones_unlocked: launch.milestones_unlocked,
+                timestamp: clock.unix_timestamp,
+            });
+        }
+
+        Ok(quote)
     }
 
     /// Sell tokens back to the launch for SOL. Only before graduation.
     /// User sends tokens back to token_vault, receives SOL at current curve price minus fees.
     pub fn sell(ctx: Context<Sell>, token_amount: u64) -> Result<()> {
-        require!(token_amount > 0, VestigeError::InvalidTokenAmount);
-
         let launch = &ctx.accounts.launch;
         let clock = Clock::get()?;
 
         require!(clock.unix_timestamp >= launch.start_time, VestigeError::LaunchNotStarted);
-        require!(!launch.is_graduated, VestigeError::AlreadyGraduated);
+        require!(launch.state.is_trading(), VestigeError::AlreadyGraduated);
 
         let position = &ctx.accounts.user_position;
         require!(position.total_base_tokens >= token_amount, VestigeError::InsufficientTokens);
 
-        // Calculate SOL to return at current demand-based price
-        let curve_price = get_curve_price(launch, launch.total_base_sold);
-        require!(curve_price > 0, VestigeError::ZeroCurvePrice);
-
-        let sol_gross = (token_amount as u128)
-            .checked_mul(curve_price as u128)
-            .ok_or(VestigeError::Overflow)?
-            .checked_div(TOKEN_PRECISION)
-            .ok_or(VestigeError::Overflow)? as u64;
-
-        require!(sol_gross > 0, VestigeError::SellAmountTooSmall);
-
-        // Calculate fees from gross
-        let protocol_fee = sol_gross
-            .checked_mul(PROTOCOL_FEE_BPS).ok_or(VestigeError::Overflow)?
-            .checked_div(BPS_DENOMINATOR).ok_or(VestigeError::Overflow)?;
-        let creator_fee = sol_gross
-            .checked_mul(CREATOR_FEE_BPS).ok_or(VestigeError::Overflow)?
-            .checked_div(BPS_DENOMINATOR).ok_or(VestigeError::Overflow)?;
-        let sol_net = sol_gross
-            .checked_sub(protocol_fee).ok_or(VestigeError::Overflow)?
-            .checked_sub(creator_fee).ok_or(VestigeError::Overflow)?;
-
-        // Check vault has enough SOL (keep rent-exempt minimum)
+        let quote = compute_sell(launch, token_amount)?;
+        let SellQuote { sol_gross, protocol_fee, creator_fee, sol_net, curve_price, .. } = quote;
+
+        // Check vault has enough quote (SOL vaults keep their rent-exempt minimum)
         let vault_info = ctx.accounts.vault.to_account_info();
-        let rent = Rent::get()?;
-        let rent_exempt_min = rent.minimum_balance(0);
-        let available = vault_info.lamports()
-            .checked_sub(rent_exempt_min).ok_or(VestigeError::InsufficientVaultFunds)?;
+        let available = vault_quote_balance(launch, &vault_info)?;
         require!(available >= sol_net, VestigeError::InsufficientVaultFunds);
 
         // Transfer tokens from user back to token_vault (user signs)
@@ -575,73 +1770,91 @@ pub mod vestige {
             token_amount,
         )?;
 
-        // Transfer SOL from vault to user (direct lamport manipulation, vault is program-owned PDA)
-        **vault_info.try_borrow_mut_lamports()? -= sol_net;
-        **ctx.accounts.user.to_account_info().try_borrow_mut_lamports()? += sol_net;
+        let launch_info = ctx.accounts.launch.to_account_info();
+        let token_info = ctx.accounts.token_program.to_account_info();
+
+        // Transfer quote from vault to user
+        let user_quote = quote_account(launch, &ctx.accounts.user.to_account_info(), ctx.accounts.user_quote_account.as_ref())?;
+        release_quote(launch, &launch_info, &vault_info, &user_quote, Some(&token_info), sol_net)?;
 
         // Transfer protocol fee from vault to treasury
-        let treasury_info = ctx.accounts.protocol_treasury.to_account_info();
-        **vault_info.try_borrow_mut_lamports()? -= protocol_fee;
-        **treasury_info.try_borrow_mut_lamports()? += protocol_fee;
+        let treasury = quote_account(launch, &ctx.accounts.protocol_treasury, ctx.accounts.treasury_quote_account.as_ref())?;
+        release_quote(launch, &launch_info, &vault_info, &treasury, Some(&token_info), protocol_fee)?;
 
         // Transfer creator fee from vault to creator_fee_vault
         let cfv_info = ctx.accounts.creator_fee_vault.to_account_info();
-        **vault_info.try_borrow_mut_lamports()? -= creator_fee;
-        **cfv_info.try_borrow_mut_lamports()? += creator_fee;
+        release_quote(launch, &launch_info, &vault_info, &cfv_info, Some(&token_info), creator_fee)?;
 
         // Update user position proportionally
         let position = &mut ctx.accounts.user_position;
-        let fraction_num = token_amount as u128;
-        let fraction_den = position.total_base_tokens as u128;
-
-        let sol_spent_reduction = (position.total_sol_spent as u128)
-            .checked_mul(fraction_num).ok_or(VestigeError::Overflow)?
-            .checked_div(fraction_den).ok_or(VestigeError::Overflow)? as u64;
-        let bonus_reduction = (position.total_bonus_entitled as u128)
-            .checked_mul(fraction_num).ok_or(VestigeError::Overflow)?
-            .checked_div(fraction_den).ok_or(VestigeError::Overflow)? as u64;
-
-        position.total_base_tokens = position.total_base_tokens
-            .checked_sub(token_amount).ok_or(VestigeError::Overflow)?;
-        position.total_sol_spent = position.total_sol_spent
-            .checked_sub(sol_spent_reduction).ok_or(VestigeError::Overflow)?;
-        position.total_bonus_entitled = position.total_bonus_entitled
-            .checked_sub(bonus_reduction).ok_or(VestigeError::Overflow)?;
+        let bonus_reduction = position.record_sell(token_amount)?;
 
         // Update launch totals
         let launch = &mut ctx.accounts.launch;
-        launch.total_base_sold = launch.total_base_sold
-            .checked_sub(token_amount).ok_or(VestigeError::Overflow)?;
-        launch.total_sol_collected = launch.total_sol_collected
-            .checked_sub(sol_net).ok_or(VestigeError::Overflow)?
-            .checked_sub(protocol_fee).ok_or(VestigeError::Overflow)?
-            .checked_sub(creator_fee).ok_or(VestigeError::Overflow)?;
-        launch.total_bonus_reserved = launch.total_bonus_reserved
-            .checked_sub(bonus_reduction).ok_or(VestigeError::Overflow)?;
-        launch.total_creator_fees = launch.total_creator_fees
-            .checked_add(creator_fee).ok_or(VestigeError::Overflow)?;
+        launch.record_sell(&quote, bonus_reduction)?;
 
         msg!("Sell: {} tokens -> {} lamports (net {} after fees)", token_amount, sol_gross, sol_net);
 
+        let position = &ctx.accounts.user_position;
+        emit!(SellEvent {
+            launch: launch.key(),
+            user: position.user,
+            token_amount,
+            sol_gross,
+            sol_net,
+            protocol_fee,
+            creator_fee,
+            bonus_forfeited: bonus_reduction,
+            curve_price,
+            position_base_tokens: position.total_base_tokens,
+            position_bonus_entitled: position.total_bonus_entitled,
+            total_base_sold: launch.total_base_sold,
+            total_sol_collected: launch.total_sol_collected,
+            timestamp: clock.unix_timestamp,
+        });
+
         Ok(())
     }
 
     /// Graduate the launch. Permissionless — anyone can call.
     /// Conditions: total SOL >= target OR time > end_time.
+    /// At the target the launch moves to Graduating (listed by `graduate_to_dex`) and the
+    /// caller earns the graduation bounty; a launch that reached its end time short of the
+    /// target moves to Failed, is never listed and pays no bounty (only `LaunchStateChanged`
+    /// is emitted).
     pub fn graduate(ctx: Context<Graduate>) -> Result<()> {
-        let launch = &mut ctx.accounts.launch;
+        let launch = &ctx.accounts.launch;
         let clock = Clock::get()?;
 
-        require!(!launch.is_graduated, VestigeError::AlreadyGraduated);
+        require!(launch.state.is_trading(), VestigeError::AlreadyGraduated);
+        require!(graduation_ready(launch, clock.unix_timestamp), VestigeError::GraduationConditionsNotMet);
 
-        let target_reached = launch.total_sol_collected >= launch.graduation_target;
-        let time_expired = clock.unix_timestamp > launch.end_time;
+        let launch_key = ctx.accounts.launch.key();
+        if launch.total_sol_collected < launch.graduation_target {
+            let launch = &mut ctx.accounts.launch;
+            transition_launch(launch, launch_key, LaunchState::Failed, clock.unix_timestamp)?;
+            msg!("=== LAUNCH ENDED SHORT OF TARGET ===");
+            msg!("Total SOL: {}", launch.total_sol_collected);
+            msg!("Total Base Sold: {}", launch.total_base_sold);
+            return Ok(());
+        }
 
-        require!(target_reached || time_expired, VestigeError::GraduationConditionsNotMet);
+        // Bounty for the caller, from the vault
+        let authority = ctx.accounts.authority.to_account_info();
+        let authority_quote = ctx.accounts.authority_quote_account.as_ref().map(|a| a.to_account_info());
+        let token_info = ctx.accounts.token_program.as_ref().map(|p| p.to_account_info());
+        let bounty = pay_graduation_bounty(
+            launch,
+            &launch.to_account_info(),
+            &ctx.accounts.vault,
+            &authority,
+            authority_quote.as_ref(),
+            token_info.as_ref(),
+        )?;
 
-        launch.is_graduated = true;
-        launch.milestones_unlocked = 1; // Unlock 30% of creator fees
-        launch.graduation_time = clock.unix_timestamp;
+        let launch = &mut ctx.accounts.launch;
+        launch.graduation_bounty_paid = bounty;
+        transition_launch(launch, launch_key, LaunchState::Graduating, clock.unix_timestamp)?;
 
         msg!("=== LAUNCH GRADUATED ===");
         msg!("Total SOL: {}", launch.total_sol_collected);
@@ -649,19 +1862,44 @@ pub mod vestige {
         msg!("Total Bonus Reserved: {}", launch.total_bonus_reserved);
         msg!("Total Participants: {}", launch.total_participants);
         msg!("Graduation Time: {}", launch.graduation_time);
+        if bounty > 0 {
+            msg!("Graduation bounty: {} to {}", bounty, authority.key());
+        }
+
+        emit!(GraduationEvent {
+            launch: launch.key(),
+            total_sol_collected: launch.total_sol_collected,
+            total_base_sold: launch.total_base_sold,
+            total_bonus_reserved: launch.total_bonus_reserved,
+            sol_released: 0,
+            tokens_released: 0,
+            pool_created: false,
+            graduation_fee: 0,
+            bounty,
+            bounty_recipient: if bounty > 0 { authority.key() } else { Pubkey::default() },
+            milestones_unlocked: launch.milestones_unlocked,
+            timestamp: clock.unix_timestamp,
+        });
 
         Ok(())
     }
 
-    /// Claim bonus tokens after graduation.
-    /// Transfers bonus_entitled tokens from token_vault to user.
+    /// Claim vested bonus tokens after graduation. Repeatable: each call transfers
+    /// whatever has vested since the last claim (see `vested_bonus`).
     pub fn claim_bonus(ctx: Context<ClaimBonus>) -> Result<()> {
         let launch = &ctx.accounts.launch;
         let position = &mut ctx.accounts.user_position;
+        let clock = Clock::get()?;
 
-        require!(launch.is_graduated, VestigeError::NotGraduated);
+        require!(launch.state.is_settled_success(), VestigeError::NotGraduated);
         require!(position.total_bonus_entitled > 0, VestigeError::NoBonusEntitled);
         require!(!position.has_claimed_bonus, VestigeError::AlreadyClaimed);
+        require!(!bonus_claim_expired(launch, clock.unix_timestamp), VestigeError::BonusClaimExpired);
+
+        let vested = vested_bonus(launch, position, clock.unix_timestamp)?;
+        let claimable = vested
+            .checked_sub(position.bonus_claimed).ok_or(VestigeError::Overflow)?;
+        require!(claimable > 0, VestigeError::BonusNotYetVested);
 
         // Transfer bonus tokens from token_vault to user
         let seeds = &[
@@ -682,94 +1920,258 @@ pub mod vestige {
                 },
                 signer_seeds,
             ),
-            position.total_bonus_entitled,
+            claimable,
         )?;
 
-        position.has_claimed_bonus = true;
+        position.bonus_claimed = vested;
+        position.has_claimed_bonus = vested == position.total_bonus_entitled;
+
+        let launch = &mut ctx.accounts.launch;
+        launch.total_bonus_claimed = launch.total_bonus_claimed
+            .checked_add(claimable).ok_or(VestigeError::Overflow)?;
 
         msg!("=== BONUS CLAIMED ===");
-        msg!("Amount: {}", position.total_bonus_entitled);
+        msg!("Amount: {} ({} of {} claimed)", claimable, position.bonus_claimed, position.total_bonus_entitled);
+
+        emit!(BonusClaimed {
+            launch: position.launch,
+            user: position.user,
+            amount: claimable,
+            total_claimed: position.bonus_claimed,
+            timestamp: clock.unix_timestamp,
+        });
 
         Ok(())
     }
 
     /// Creator claims vested fees from the creator_fee_vault after graduation.
-    /// Fees vest based on milestones: 30% at graduation, then 20%, 20%, 30%.
+    /// Fees vest by the launch's milestone schedule (default 30% at graduation, then 20%, 20%, 30%),
+    /// or stream linearly from graduation when `fee_stream_duration` is set.
+    /// With a fee split table each recipient claims its own share of what has vested;
+    /// otherwise the whole amount goes to `fee_authority`.
     pub fn creator_claim_fees(ctx: Context<CreatorClaimFees>) -> Result<()> {
         let launch = &ctx.accounts.launch;
+        let recipient = ctx.accounts.recipient.key();
+        let clock = Clock::get()?;
 
-        require!(launch.is_graduated, VestigeError::NotGraduated);
-        require!(ctx.accounts.creator.key() == launch.creator, VestigeError::Unauthorized);
-        require!(launch.milestones_unlocked > 0, VestigeError::NoMilestonesUnlocked);
+        require!(launch.state.is_settled_success(), VestigeError::NotGraduated);
+        require!(
+            launch.fee_stream_duration > 0 || launch.milestones_unlocked > 0,
+            VestigeError::NoMilestonesUnlocked
+        );
 
-        // Calculate unlocked percentage based on milestone level
-        let unlocked_bps: u64 = match launch.milestones_unlocked {
-            1 => VEST_GRADUATION,                                    // 3000 = 30%
-            2 => VEST_GRADUATION + VEST_M1,                          // 5000 = 50%
-            3 => VEST_GRADUATION + VEST_M1 + VEST_M2,                // 7000 = 70%
-            _ => BPS_DENOMINATOR,                                     // 10000 = 100%
+        let total_unlocked = creator_fees_unlocked(launch, clock.unix_timestamp)?;
+
+        // Recipient's share of the unlocked fees and what it has already taken
+        let (split_index, unlocked, claimed) = if launch.fee_split_count == 0 {
+            require!(recipient == launch.fee_authority, VestigeError::Unauthorized);
+            (None, total_unlocked, launch.creator_fees_claimed)
+        } else {
+            let index = launch.fee_splits()
+                .iter()
+                .position(|split| split.recipient == recipient)
+                .ok_or(VestigeError::Unauthorized)?;
+            let share = (total_unlocked as u128)
+                .checked_mul(launch.fee_splits[index].bps as u128).ok_or(VestigeError::Overflow)?
+                .checked_div(BPS_DENOMINATOR as u128).ok_or(VestigeError::Overflow)? as u64;
+            (Some(index), share, launch.fee_split_claimed[index])
         };
 
-        let total_unlocked = launch.total_creator_fees
-            .checked_mul(unlocked_bps).ok_or(VestigeError::Overflow)?
-            .checked_div(BPS_DENOMINATOR).ok_or(VestigeError::Overflow)?;
-
-        let claimable = total_unlocked
-            .checked_sub(launch.creator_fees_claimed).ok_or(VestigeError::Overflow)?;
+        let claimable = unlocked
+            .checked_sub(claimed).ok_or(VestigeError::Overflow)?;
         require!(claimable > 0, VestigeError::NothingToWithdraw);
 
-        // Transfer from creator_fee_vault to creator (direct lamport manipulation)
+        // Transfer from creator_fee_vault to recipient (or its quote token account)
         let vault_info = ctx.accounts.creator_fee_vault.to_account_info();
-        **vault_info.try_borrow_mut_lamports()? -= claimable;
-        **ctx.accounts.creator.to_account_info().try_borrow_mut_lamports()? += claimable;
+        let to = quote_account(launch, &ctx.accounts.recipient.to_account_info(), ctx.accounts.recipient_quote_account.as_ref())?;
+        let token_info = ctx.accounts.token_program.as_ref().map(|p| p.to_account_info());
+        release_quote(launch, &ctx.accounts.launch.to_account_info(), &vault_info, &to, token_info.as_ref(), claimable)?;
 
-        // Update claimed amount
+        // Update claimed amounts
         let launch = &mut ctx.accounts.launch;
+        if let Some(index) = split_index {
+            launch.fee_split_claimed[index] = launch.fee_split_claimed[index]
+                .checked_add(claimable).ok_or(VestigeError::Overflow)?;
+        }
         launch.creator_fees_claimed = launch.creator_fees_claimed
             .checked_add(claimable).ok_or(VestigeError::Overflow)?;
 
         msg!("=== CREATOR FEES CLAIMED ===");
-        msg!("Amount: {} lamports", claimable);
-        msg!("Milestone: {}/4", launch.milestones_unlocked);
+        msg!("Amount: {}", claimable);
+        msg!("Milestone: {}/{}", launch.milestones_unlocked, launch.fee_vest_step_count);
+
+        emit!(CreatorFeesClaimed {
+            launch: launch.key(),
+            recipient,
+            amount: claimable,
+            milestones_unlocked: launch.milestones_unlocked,
+            timestamp: clock.unix_timestamp,
+        });
 
         Ok(())
     }
 
     /// Advance milestone to unlock more creator fees.
-    /// Creator-only, time-locked: each milestone requires MILESTONE_INTERVAL seconds after the previous.
+    /// Permissionless (keepers crank it), time-locked: each milestone requires its
+    /// schedule step's delay to have passed since graduation, plus its optional
+    /// performance condition, verified from the remaining accounts (see
+    /// `check_milestone_condition`). Claiming stays creator-only.
     pub fn advance_milestone(ctx: Context<AdvanceMilestone>) -> Result<()> {
         let launch = &mut ctx.accounts.launch;
         let clock = Clock::get()?;
 
-        require!(launch.is_graduated, VestigeError::NotGraduated);
-        require!(launch.milestones_unlocked < 4, VestigeError::AllMilestonesUnlocked);
-
-        // Only the creator can advance milestones
+        require!(launch.state.is_settled_success(), VestigeError::NotGraduated);
+        require!(launch.fee_stream_duration == 0, VestigeError::FeesStreamed);
         require!(
-            ctx.accounts.creator.key() == launch.creator,
-            VestigeError::Unauthorized
+            launch.milestones_unlocked < launch.fee_vest_step_count,
+            VestigeError::AllMilestonesUnlocked
         );
 
-        // Time-lock: milestone N+1 requires graduation_time + MILESTONE_INTERVAL * (milestones_unlocked)
-        // milestones_unlocked is currently 1,2,3 — we want:
-        //   milestone 2: graduation_time + 1 * interval
-        //   milestone 3: graduation_time + 2 * interval
-        //   milestone 4: graduation_time + 3 * interval
-        let intervals = launch.milestones_unlocked as i64; // 1, 2, or 3
-        let required_time = launch.graduation_time
-            .checked_add(MILESTONE_INTERVAL.checked_mul(intervals).ok_or(VestigeError::Overflow)?)
-            .ok_or(VestigeError::Overflow)?;
+        let required_time = next_milestone_time(launch).ok_or(VestigeError::Overflow)?;
 
         require!(
             clock.unix_timestamp >= required_time,
             VestigeError::MilestoneNotYetUnlocked
         );
 
+        let condition = launch.fee_vest_steps()[launch.milestones_unlocked as usize].condition;
+        check_milestone_condition(launch, &launch.key(), condition, ctx.remaining_accounts)?;
+
         launch.milestones_unlocked = launch.milestones_unlocked
             .checked_add(1).ok_or(VestigeError::Overflow)?;
 
         msg!("=== MILESTONE ADVANCED ===");
-        msg!("New milestone level: {}/4", launch.milestones_unlocked);
+        msg!("New milestone level: {}/{}", launch.milestones_unlocked, launch.fee_vest_step_count);
+
+        emit!(MilestoneAdvanced {
+            launch: launch.key(),
+            milestones_unlocked: launch.milestones_unlocked,
+            unlocked_bps: fee_unlocked_bps(launch),
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Propose handing the creator role (initial buy, fee claims) to `new_authority`.
+    /// Takes effect only once `new_authority` calls `accept_creator_transfer`; proposing
+    /// `Pubkey::default()` cancels a pending transfer. `Launch.creator` stays unchanged
+    /// because it is part of the launch PDA seeds.
+    pub fn propose_creator_transfer(ctx: Context<ProposeCreatorTransfer>, new_authority: Pubkey) -> Result<()> {
+        let launch = &mut ctx.accounts.launch;
+
+        require!(ctx.accounts.fee_authority.key() == launch.fee_authority, VestigeError::Unauthorized);
+        require!(new_authority != launch.fee_authority, VestigeError::InvalidCreatorTransfer);
+
+        launch.pending_fee_authority = new_authority;
+
+        msg!("Creator transfer proposed: {} -> {}", launch.fee_authority, new_authority);
+
+        emit!(CreatorTransferProposed {
+            launch: launch.key(),
+            fee_authority: launch.fee_authority,
+            pending_fee_authority: new_authority,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Accept a pending creator transfer. Must be signed by the proposed authority.
+    pub fn accept_creator_transfer(ctx: Context<AcceptCreatorTransfer>) -> Result<()> {
+        let launch = &mut ctx.accounts.launch;
+
+        require!(launch.pending_fee_authority != Pubkey::default(), VestigeError::NoPendingCreatorTransfer);
+        require!(ctx.accounts.new_authority.key() == launch.pending_fee_authority, VestigeError::Unauthorized);
+
+        let previous_fee_authority = launch.fee_authority;
+        launch.fee_authority = launch.pending_fee_authority;
+        launch.pending_fee_authority = Pubkey::default();
+
+        msg!("Creator transfer accepted: {} -> {}", previous_fee_authority, launch.fee_authority);
+
+        emit!(CreatorTransferAccepted {
+            launch: launch.key(),
+            previous_fee_authority,
+            fee_authority: launch.fee_authority,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Create the protocol-wide settings account. Must be signed by PROTOCOL_TREASURY,
+    /// which becomes its admin.
+    pub fn initialize_protocol_config(
+        ctx: Context<InitializeProtocolConfig>,
+        referral_share_bps: u64,
+        graduation_fee_bps: u64,
+    ) -> Result<()> {
+        require!(referral_share_bps <= MAX_REFERRAL_SHARE_BPS, VestigeError::InvalidProtocolConfig);
+        require!(graduation_fee_bps <= MAX_GRADUATION_FEE_BPS, VestigeError::InvalidProtocolConfig);
+
+        let config = &mut ctx.accounts.protocol_config;
+        config.admin = ctx.accounts.admin.key();
+        config.referral_share_bps = referral_share_bps;
+        config.graduation_fee_bps = graduation_fee_bps;
+        config.bump = ctx.bumps.protocol_config;
+
+        msg!("Protocol config initialized: referral share {} bps, graduation fee {} bps", referral_share_bps, graduation_fee_bps);
+        Ok(())
+    }
+
+    /// Update the protocol-wide settings. Admin only. A new graduation fee applies to
+    /// launches created afterwards (each launch sizes its pool for the fee at creation).
+    pub fn update_protocol_config(
+        ctx: Context<UpdateProtocolConfig>,
+        referral_share_bps: u64,
+        graduation_fee_bps: u64,
+    ) -> Result<()> {
+        require!(referral_share_bps <= MAX_REFERRAL_SHARE_BPS, VestigeError::InvalidProtocolConfig);
+        require!(graduation_fee_bps <= MAX_GRADUATION_FEE_BPS, VestigeError::InvalidProtocolConfig);
+
+        let config = &mut ctx.accounts.protocol_config;
+        config.referral_share_bps = referral_share_bps;
+        config.graduation_fee_bps = graduation_fee_bps;
+
+        msg!("Protocol config updated: referral share {} bps, graduation fee {} bps", referral_share_bps, graduation_fee_bps);
+        Ok(())
+    }
+
+    /// Register the signer as a referrer. Buys that pass its Referrer PDA send it
+    /// `referral_share_bps` of the protocol fee.
+    pub fn register_referrer(ctx: Context<RegisterReferrer>) -> Result<()> {
+        let referrer = &mut ctx.accounts.referrer;
+        referrer.authority = ctx.accounts.authority.key();
+        referrer.bump = ctx.bumps.referrer;
+
+        msg!("Referrer registered: {}", referrer.authority);
+        Ok(())
+    }
+
+    /// Withdraw accrued referral fees from the Referrer PDA to its authority.
+    pub fn claim_referral_fees(ctx: Context<ClaimReferralFees>) -> Result<()> {
+        let referrer = &mut ctx.accounts.referrer;
+
+        let claimable = referrer.total_earned
+            .checked_sub(referrer.total_claimed).ok_or(VestigeError::Overflow)?;
+        require!(claimable > 0, VestigeError::NothingToWithdraw);
+
+        **referrer.to_account_info().try_borrow_mut_lamports()? -= claimable;
+        **ctx.accounts.authority.to_account_info().try_borrow_mut_lamports()? += claimable;
+
+        referrer.total_claimed = referrer.total_claimed
+            .checked_add(claimable).ok_or(VestigeError::Overflow)?;
+
+        msg!("=== REFERRAL FEES CLAIMED ===");
+        msg!("Amount: {} lamports", claimable);
+
+        emit!(ReferralFeesClaimed {
+            referrer: referrer.key(),
+            authority: referrer.authority,
+            amount: claimable,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
 
         Ok(())
     }
@@ -781,79 +2183,489 @@ pub mod vestige {
     ///   2. SystemProgram.transfer(payer → payer_wsol_ata) — wraps SOL
     ///   3. SyncNative(payer_wsol_ata) — syncs wSOL balance
     ///   4. Raydium CPMM initialize — creates the pool using the released assets
-    /// Permissionless — anyone can call once graduation conditions are met.
+    /// Quote-mint launches release the vault's tokens to `payer_quote_account` instead,
+    /// so steps 2–3 are skipped and the pool pairs the token with the quote mint.
+    /// Permissionless — anyone can call once graduation conditions are met, including
+    /// after `graduate` has already flipped the launch to graduated.
     pub fn graduate_to_dex(ctx: Context<GraduateToDex>) -> Result<()> {
         let clock = Clock::get()?;
 
         // Cache launch fields before any mutable borrow
-        let (creator, token_mint_key, bump, lp_reserve,
-             is_graduated, pool_created, total_sol_collected,
-             graduation_target) = {
+        let (launch_key, state, total_sol_collected, graduation_target) = {
             let l = &ctx.accounts.launch;
-            (l.creator, l.token_mint, l.bump, l.lp_reserve,
-             l.is_graduated, l.pool_created, l.total_sol_collected,
-             l.graduation_target)
+            (l.key(), l.state, l.total_sol_collected, l.graduation_target)
         };
 
-        require!(!is_graduated, VestigeError::AlreadyGraduated);
-        require!(!pool_created, VestigeError::PoolAlreadyCreated);
+        // A launch already graduated by `graduate` (e.g. a keeper crank) or by the buy
+        // that reached the target waits in Graduating and can still be listed; only
+        // Graduated means the liquidity was already released.
+        require!(state != LaunchState::Graduated, VestigeError::PoolAlreadyCreated);
         // Graduation requires the SOL target to be reached — no time expiry
         require!(total_sol_collected >= graduation_target, VestigeError::GraduationConditionsNotMet);
 
-        // Compute amounts
-        let rent = Rent::get()?;
-        let rent_exempt_min = rent.minimum_balance(0);
-        let vault_lamports = ctx.accounts.vault.to_account_info().lamports();
+        // Bounty for the caller, unless `graduate` already paid it (a launch moved to
+        // Graduating by the buy that reached the target has not been paid one)
+        let vault_info = ctx.accounts.vault.to_account_info();
+        let payer_info = ctx.accounts.payer.to_account_info();
+        let token_info = ctx.accounts.token_program.to_account_info();
+        let bounty_owed = graduation_bounty_owed(&ctx.accounts.launch);
+        let bounty = if !bounty_owed {
+            0
+        } else {
+            let launch = &ctx.accounts.launch;
+            let payer_quote = ctx.accounts.payer_quote_account.as_ref().map(|a| a.to_account_info());
+            pay_graduation_bounty(
+                launch,
+                &launch.to_account_info(),
+                &vault_info,
+                &payer_info,
+                payer_quote.as_ref(),
+                Some(&token_info),
+            )?
+        };
+        if bounty_owed {
+            ctx.accounts.launch.graduation_bounty_paid = bounty;
+        }
+
+        let launch = &ctx.accounts.launch;
+        let to = quote_account(launch, &payer_info, ctx.accounts.payer_quote_account.as_ref())?;
+        let (graduation_fee, sol_for_pool, tokens_for_pool, lp_leftover) = release_pool_liquidity(
+            launch,
+            &launch.to_account_info(),
+            &vault_info,
+            &ctx.accounts.token_vault.to_account_info(),
+            &ctx.accounts.protocol_treasury,
+            ctx.accounts.treasury_quote_account.as_ref(),
+            &to,
+            &ctx.accounts.payer_token_account,
+            &token_info,
+        )?;
+
+        // Mark graduated — client is responsible for creating the Raydium pool
+        // in the same atomic transaction using the released SOL and tokens.
+        let launch = &mut ctx.accounts.launch;
+        launch.leftover_released = launch.leftover_released
+            .checked_add(lp_leftover).ok_or(VestigeError::Overflow)?;
+        transition_launch(launch, launch_key, LaunchState::Graduated, clock.unix_timestamp)?;
+
+        msg!("=== LAUNCH GRADUATED ===");
+        msg!("SOL released: {} lamports", sol_for_pool);
+        msg!("Tokens released: {}", tokens_for_pool);
+        if graduation_fee > 0 {
+            msg!("Graduation fee: {} to protocol treasury", graduation_fee);
+        }
+        if bounty > 0 {
+            msg!("Graduation bounty: {} to {}", bounty, payer_info.key());
+        }
+
+        emit!(GraduationEvent {
+            launch: launch.key(),
+            total_sol_collected: launch.total_sol_collected,
+            total_base_sold: launch.total_base_sold,
+            total_bonus_reserved: launch.total_bonus_reserved,
+            sol_released: sol_for_pool,
+            tokens_released: tokens_for_pool,
+            pool_created: true,
+            graduation_fee,
+            bounty,
+            bounty_recipient: if bounty > 0 { payer_info.key() } else { Pubkey::default() },
+            milestones_unlocked: launch.milestones_unlocked,
+            timestamp: clock.unix_timestamp,
+        });
 
-        require!(vault_lamports > rent_exempt_min, VestigeError::InsufficientPoolLiquidity);
-        let sol_for_pool = vault_lamports - rent_exempt_min;
+        Ok(())
+    }
+
+    /// Apply the launch's leftover policy to tokens no buyer is owed (`leftover_tokens`).
+    /// Permissionless after graduation; repeatable as more is released or swept.
+    ///   Burn          — burns them
+    ///   VestToCreator — sends the part vested so far (linear from graduation) to
+    ///                   `fee_authority`'s token account
+    ///   AddToLp       — they go into the pool in `graduate_to_dex`; bonus swept after
+    ///                   the pool exists can no longer be listed, so it is burned
+    pub fn release_leftover_tokens(ctx: Context<ReleaseLeftoverTokens>) -> Result<()> {
+        let launch = &ctx.accounts.launch;
+        let clock = Clock::get()?;
+
+        require!(launch.state.curve_closed(), VestigeError::NotGraduated);
+
+        let leftover = leftover_tokens(launch)?;
+        let (releasable, to_creator) = match launch.leftover_policy {
+            LeftoverPolicy::Burn => (leftover, false),
+            LeftoverPolicy::AddToLp => {
+                require!(launch.state == LaunchState::Graduated, VestigeError::LeftoverReservedForLp);
+                (leftover, false)
+            }
+            LeftoverPolicy::VestToCreator(duration) => {
+                let elapsed = clock.unix_timestamp
+                    .saturating_sub(launch.graduation_time)
+                    .clamp(0, duration);
+                let vested = (leftover as u128)
+                    .checked_mul(elapsed as u128).ok_or(VestigeError::Overflow)?
+                    .checked_div(duration as u128).ok_or(VestigeError::Overflow)?;
+                (vested as u64, true)
+            }
+        };
+        let amount = releasable.saturating_sub(launch.leftover_released);
+        require!(amount > 0, VestigeError::NothingToWithdraw);
+
+        let seeds = &[LAUNCH_SEED, launch.creator.as_ref(), launch.token_mint.as_ref(), &[launch.bump]];
+        let signer_seeds = &[&seeds[..]];
+        let token_info = ctx.accounts.token_program.to_account_info();
+        if to_creator {
+            let creator_token_account = ctx.accounts.creator_token_account.as_ref()
+                .ok_or(VestigeError::InvalidUserTokenAccount)?;
+            token::transfer(
+                CpiContext::new_with_signer(
+                    token_info,
+                    Transfer {
+                        from: ctx.accounts.token_vault.to_account_info(),
+                        to: creator_token_account.to_account_info(),
+                        authority: ctx.accounts.launch.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                amount,
+            )?;
+        } else {
+            token::burn(
+                CpiContext::new_with_signer(
+                    token_info,
+                    Burn {
+                        mint: ctx.accounts.token_mint.to_account_info(),
+                        from: ctx.accounts.token_vault.to_account_info(),
+                        authority: ctx.accounts.launch.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                amount,
+            )?;
+        }
+
+        let launch = &mut ctx.accounts.launch;
+        launch.leftover_released = launch.leftover_released
+            .checked_add(amount).ok_or(VestigeError::Overflow)?;
+
+        msg!("Leftover tokens {}: {} ({} of {} released)",
+            if to_creator { "returned" } else { "burned" }, amount, launch.leftover_released, leftover);
+
+        emit!(LeftoverTokensReleased {
+            launch: launch.key(),
+            amount,
+            burned: !to_creator,
+            total_released: launch.leftover_released,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Forfeit every bonus still unclaimed at the launch's bonus claim deadline
+    /// (`graduation_time + bonus_claim_deadline`). Permissionless. The swept tokens
+    /// join the leftover and follow the launch's leftover policy.
+    pub fn sweep_unclaimed_bonus(ctx: Context<SweepUnclaimedBonus>) -> Result<()> {
+        let launch = &mut ctx.accounts.launch;
+        let clock = Clock::get()?;
+
+        require!(bonus_claim_expired(launch, clock.unix_timestamp), VestigeError::BonusClaimWindowOpen);
+
+        let unclaimed = launch.total_bonus_reserved
+            .saturating_sub(launch.total_bonus_claimed)
+            .saturating_sub(launch.bonus_swept);
+        require!(unclaimed > 0, VestigeError::NothingToWithdraw);
+
+        launch.bonus_swept = launch.bonus_swept
+            .checked_add(unclaimed).ok_or(VestigeError::Overflow)?;
+
+        msg!("Unclaimed bonus swept: {}", unclaimed);
+
+        emit!(UnclaimedBonusSwept {
+            launch: launch.key(),
+            amount: unclaimed,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Return base tokens to a failed launch for a pro-rata share of its vault and of its
+    /// creator fee vault, as a failed launch pays no creator fees (see `failed_launch_refund`).
+    /// No fees are taken; the returned tokens join the leftover and the position forfeits
+    /// the matching share of its bonus. Only tokens still recorded on the caller's position
+    /// can be refunded, and not once any of its bonus was claimed.
+    pub fn refund(ctx: Context<Refund>, token_amount: u64) -> Result<()> {
+        let launch = &ctx.accounts.launch;
+        let clock = Clock::get()?;
+
+        require!(launch.state == LaunchState::Failed, VestigeError::LaunchNotFailed);
+        require!(token_amount > 0, VestigeError::InvalidTokenAmount);
+        let position = &ctx.accounts.user_position;
+        require!(position.total_base_tokens >= token_amount, VestigeError::InsufficientTokens);
+        require!(position.bonus_claimed == 0, VestigeError::BonusAlreadyClaimed);
+
+        let vault_info = ctx.accounts.vault.to_account_info();
+        let fee_vault_info = ctx.accounts.creator_fee_vault.to_account_info();
+        let sol_refund = failed_launch_refund(launch, vault_quote_balance(launch, &vault_info)?, token_amount)?;
+        let fee_refund = failed_launch_refund(launch, vault_quote_balance(launch, &fee_vault_info)?, token_amount)?;
+        let refund = sol_refund.checked_add(fee_refund).ok_or(VestigeError::Overflow)?;
+
+        // Tokens back to the token vault (user signs)
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.user_token_account.to_account_info(),
+                    to: ctx.accounts.token_vault.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            token_amount,
+        )?;
+
+        let launch_info = ctx.accounts.launch.to_account_info();
+        let token_info = ctx.accounts.token_program.to_account_info();
+        let user_quote = quote_account(launch, &ctx.accounts.user.to_account_info(), ctx.accounts.user_quote_account.as_ref())?;
+        if sol_refund > 0 {
+            release_quote(launch, &launch_info, &vault_info, &user_quote, Some(&token_info), sol_refund)?;
+        }
+        if fee_refund > 0 {
+            release_quote(launch, &launch_info, &fee_vault_info, &user_quote, Some(&token_info), fee_refund)?;
+        }
+
+        let position = &mut ctx.accounts.user_position;
+        let bonus_forfeited = position.record_sell(token_amount)?;
+
+        let launch = &mut ctx.accounts.launch;
+        launch.total_base_sold = launch.total_base_sold
+            .checked_sub(token_amount).ok_or(VestigeError::Overflow)?;
+        launch.total_sol_collected = launch.total_sol_collected.saturating_sub(sol_refund);
+        launch.total_bonus_reserved = launch.total_bonus_reserved
+            .checked_sub(bonus_forfeited).ok_or(VestigeError::Overflow)?;
+
+        msg!("Refund: {} tokens -> {}", token_amount, refund);
 
-        // Always use the fixed lp_reserve for pool creation — this guarantees
-        // the Raydium listing price equals p_min (the curve's endpoint price).
-        let tokens_for_pool = lp_reserve;
+        let position = &ctx.accounts.user_position;
+        emit!(RefundEvent {
+            launch: launch.key(),
+            user: position.user,
+            token_amount,
+            refund,
+            bonus_forfeited,
+            position_base_tokens: position.total_base_tokens,
+            position_bonus_entitled: position.total_bonus_entitled,
+            total_base_sold: launch.total_base_sold,
+            total_sol_collected: launch.total_sol_collected,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
 
-        require!(sol_for_pool > 0, VestigeError::InsufficientPoolLiquidity);
-        require!(tokens_for_pool > 0, VestigeError::InsufficientPoolLiquidity);
+    /// Cancel a launch before the creator's activating buy. Only `fee_authority` may cancel:
+    /// every token in the token vault goes to its token account, and the launch, vault,
+    /// creator fee vault and token vault are closed with their rent refunded to it. The
+    /// mint's Metaplex metadata stays (the program cannot close it).
+    pub fn cancel_launch(ctx: Context<CancelLaunch>) -> Result<()> {
+        let launch_key = ctx.accounts.launch.key();
+        let launch = &mut ctx.accounts.launch;
+        require!(launch.state == LaunchState::Pending, VestigeError::LaunchAlreadyActive);
+        transition_launch(launch, launch_key, LaunchState::Cancelled, Clock::get()?.unix_timestamp)?;
 
-        // Transfer SOL from vault to payer (direct lamport — no subsequent CPI on payer needed)
-        **ctx.accounts.vault.to_account_info().try_borrow_mut_lamports()? -= sol_for_pool;
-        **ctx.accounts.payer.to_account_info().try_borrow_mut_lamports()? += sol_for_pool;
+        let launch = &ctx.accounts.launch;
 
-        // Transfer tokens from token_vault to payer_token_account (launch PDA signs)
-        let seeds = &[LAUNCH_SEED, creator.as_ref(), token_mint_key.as_ref(), &[bump]];
+        let launch_info = ctx.accounts.launch.to_account_info();
+        let authority_info = ctx.accounts.fee_authority.to_account_info();
+        let token_info = ctx.accounts.token_program.to_account_info();
+        let seeds = &[LAUNCH_SEED, launch.creator.as_ref(), launch.token_mint.as_ref(), &[launch.bump]];
         let signer_seeds = &[&seeds[..]];
+
+        // Return every token to fee_authority, then close the token vault
+        let tokens_returned = ctx.accounts.token_vault.amount;
         token::transfer(
             CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
+                token_info.clone(),
                 Transfer {
                     from: ctx.accounts.token_vault.to_account_info(),
-                    to: ctx.accounts.payer_token_account.to_account_info(),
-                    authority: ctx.accounts.launch.to_account_info(),
+                    to: ctx.accounts.creator_token_account.to_account_info(),
+                    authority: launch_info.clone(),
                 },
                 signer_seeds,
             ),
-            tokens_for_pool,
+            tokens_returned,
         )?;
+        token::close_account(CpiContext::new_with_signer(
+            token_info.clone(),
+            CloseAccount {
+                account: ctx.accounts.token_vault.to_account_info(),
+                destination: authority_info.clone(),
+                authority: launch_info.clone(),
+            },
+            signer_seeds,
+        ))?;
+
+        // Close both vaults; the launch itself is closed by the context
+        let creator_quote = ctx.accounts.creator_quote_account.as_ref().map(|a| a.to_account_info());
+        for vault in [&ctx.accounts.vault, &ctx.accounts.creator_fee_vault] {
+            close_vault(launch, &launch_info, vault, &authority_info, creator_quote.as_ref(), &token_info)?;
+        }
 
-        // Mark graduated — client is responsible for creating the Raydium pool
-        // in the same atomic transaction using the released SOL and tokens.
-        let launch = &mut ctx.accounts.launch;
-        launch.is_graduated = true;
-        launch.pool_created = true;
-        launch.milestones_unlocked = 1;
-        launch.graduation_time = clock.unix_timestamp;
+        msg!("=== LAUNCH CANCELLED ===");
+        msg!("Tokens returned: {}", tokens_returned);
 
-        msg!("=== LAUNCH GRADUATED ===");
-        msg!("SOL released: {} lamports", sol_for_pool);
-        msg!("Tokens released: {}", tokens_for_pool);
+        emit!(LaunchCancelled {
+            launch: launch.key(),
+            creator: launch.creator,
+            tokens_returned,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Close a settled position (see `position_settled`), refunding its rent to the user.
+    /// Once `close_launch` has closed the launch every position is settled, so it can
+    /// always be closed.
+    pub fn close_position(ctx: Context<ClosePosition>) -> Result<()> {
+        let position = &ctx.accounts.user_position;
+        let clock = Clock::get()?;
+
+        let launch_info = &ctx.accounts.launch;
+        if !launch_info.data_is_empty() {
+            require_keys_eq!(*launch_info.owner, crate::ID, VestigeError::PositionMismatch);
+            let launch = Launch::try_deserialize(&mut &launch_info.try_borrow_data()?[..])?;
+            require!(
+                position_settled(&launch, position, clock.unix_timestamp),
+                VestigeError::PositionNotSettled
+            );
+        }
+
+        msg!("Position closed: {}", position.user);
+        Ok(())
+    }
+
+    /// Close a finished launch: a Graduated one once every bonus, leftover and creator fee
+    /// is settled, a Failed one once every holder has refunded (see `launch_settled`).
+    /// Only `fee_authority` may close it: the launch, vault, creator fee vault and token
+    /// vault are closed and their rent (and any dust) refunded to it. Tokens still in the
+    /// token vault are leftovers nobody is owed, so they are burned.
+    pub fn close_launch(ctx: Context<CloseLaunch>) -> Result<()> {
+        let launch = &ctx.accounts.launch;
+        let clock = Clock::get()?;
+
+        require!(launch_settled(launch, clock.unix_timestamp)?, VestigeError::LaunchNotSettled);
+
+        let launch_info = ctx.accounts.launch.to_account_info();
+        let authority_info = ctx.accounts.fee_authority.to_account_info();
+        let token_info = ctx.accounts.token_program.to_account_info();
+        let seeds = &[LAUNCH_SEED, launch.creator.as_ref(), launch.token_mint.as_ref(), &[launch.bump]];
+        let signer_seeds = &[&seeds[..]];
+
+        // Burn what is left in the token vault, then close it
+        let tokens_burned = ctx.accounts.token_vault.amount;
+        if tokens_burned > 0 {
+            token::burn(
+                CpiContext::new_with_signer(
+                    token_info.clone(),
+                    Burn {
+                        mint: ctx.accounts.token_mint.to_account_info(),
+                        from: ctx.accounts.token_vault.to_account_info(),
+                        authority: launch_info.clone(),
+                    },
+                    signer_seeds,
+                ),
+                tokens_burned,
+            )?;
+        }
+        token::close_account(CpiContext::new_with_signer(
+            token_info.clone(),
+            CloseAccount {
+                account: ctx.accounts.token_vault.to_account_info(),
+                destination: authority_info.clone(),
+                authority: launch_info.clone(),
+            },
+            signer_seeds,
+        ))?;
+
+        // Close both vaults; the launch itself is closed by the context
+        let creator_quote = ctx.accounts.creator_quote_account.as_ref().map(|a| a.to_account_info());
+        for vault in [&ctx.accounts.vault, &ctx.accounts.creator_fee_vault] {
+            close_vault(launch, &launch_info, vault, &authority_info, creator_quote.as_ref(), &token_info)?;
+        }
+
+        msg!("=== LAUNCH CLOSED ===");
+        msg!("Tokens burned: {}", tokens_burned);
+
+        emit!(LaunchClosed {
+            launch: launch.key(),
+            creator: launch.creator,
+            tokens_burned,
+            timestamp: clock.unix_timestamp,
+        });
 
         Ok(())
     }
+
+    /// Read-only: quote a buy of `sol_amount` lamports at the current curve state.
+    /// Result is returned via `set_return_data` — call with `simulateTransaction`
+    /// (Anchor `.view()`) or via CPI and read the return data.
+    pub fn quote_buy(ctx: Context<ViewLaunch>, sol_amount: u64) -> Result<BuyQuote> {
+        let launch = &ctx.accounts.launch;
+        let clock = Clock::get()?;
+
+        require!(clock.unix_timestamp >= launch.start_time, VestigeError::LaunchNotStarted);
+        require!(launch.state.is_trading(), VestigeError::AlreadyGraduated);
+
+        compute_buy(launch, sol_amount, clock.unix_timestamp)
+    }
+
+    /// Read-only: quote a sell of `token_amount` base tokens at the current curve state.
+    /// Does not check the caller's position — only the curve and the vault.
+    pub fn quote_sell(ctx: Context<ViewLaunch>, token_amount: u64) -> Result<SellQuote> {
+        let launch = &ctx.accounts.launch;
+        let clock = Clock::get()?;
+
+        require!(clock.unix_timestamp >= launch.start_time, VestigeError::LaunchNotStarted);
+        require!(launch.state.is_trading(), VestigeError::AlreadyGraduated);
+
+        compute_sell(launch, token_amount)
+    }
+
+    /// Read-only: snapshot of the launch's live curve state (price, weight, progress, vesting).
+    pub fn get_launch_view(ctx: Context<ViewLaunch>) -> Result<LaunchView> {
+        let launch = &ctx.accounts.launch;
+        let clock = Clock::get()?;
+
+        Ok(LaunchView {
+            current_price: get_curve_price(launch, launch.total_base_sold),
+            current_weight_scaled: get_risk_weight_scaled(launch, clock.unix_timestamp) as u64,
+            graduation_progress_bps: get_graduation_progress_bps(launch, launch.total_sol_collected),
+            total_sol_collected: launch.total_sol_collected,
+            graduation_target: launch.graduation_target,
+            total_base_sold: launch.total_base_sold,
+            remaining_supply: launch.token_supply.saturating_sub(launch.total_base_sold),
+            total_bonus_reserved: launch.total_bonus_reserved,
+            remaining_bonus: launch.bonus_pool.saturating_sub(launch.total_bonus_reserved),
+            total_participants: launch.total_participants,
+            state: launch.state,
+            has_initial_buy: launch.has_initial_buy,
+            is_graduated: launch.is_graduated,
+            quote_mint: launch.quote_mint,
+            pool_created: launch.pool_created,
+            milestones_unlocked: launch.milestones_unlocked,
+            milestone_count: launch.fee_vest_step_count,
+            fee_unlocked_bps: fee_unlocked_bps(launch),
+            fee_stream_duration: launch.fee_stream_duration,
+            creator_fees_unlocked: creator_fees_unlocked(launch, clock.unix_timestamp)?,
+            total_creator_fees: launch.total_creator_fees,
+            creator_fees_claimed: launch.creator_fees_claimed,
+        })
+    }
 }
 
 // ============== Account Structures ==============
 
 #[account]
+#[derive(Default)]
 pub struct Launch {
     pub creator: Pubkey,              // 32
     pub token_mint: Pubkey,           // 32
@@ -871,49 +2683,721 @@ pub struct Launch {
     pub total_bonus_reserved: u64,    // 8
     pub total_sol_collected: u64,     // 8
     pub total_participants: u64,      // 8
-    pub is_graduated: bool,           // 1
+    pub is_graduated: bool,           // 1 — mirrors `state`: Graduating or Graduated
     pub bump: u8,                     // 1
     pub total_creator_fees: u64,      // 8
     pub creator_fees_claimed: u64,    // 8
     pub milestones_unlocked: u8,      // 1
-    pub has_initial_buy: bool,        // 1
+    pub has_initial_buy: bool,        // 1 — mirrors `state`: set once it reaches Active
     pub name: [u8; 32],              // 32
     pub symbol: [u8; 10],            // 10
     pub graduation_time: i64,         // 8
     pub vault_bump: u8,               // 1
     pub creator_fee_vault_bump: u8,   // 1
-    pub pool_created: bool,           // 1 — true after graduate_to_dex succeeds
-    pub lp_reserve: u64,              // 8 — tokens reserved for Raydium LP (never sold during curve)
-                                      //     p_min = graduation_target * TOKEN_PRECISION / lp_reserve
+    pub pool_created: bool,           // 1 — mirrors `state`: Graduated
+    pub lp_reserve: u64,              // 8 — tokens seeded into the Raydium pool (never sold during curve):
+                                      //     the reserve minus lp_reserve_withheld, so
+                                      //     p_min = graduation_target * TOKEN_PRECISION / (lp_reserve + lp_reserve_withheld)
                                       //     p_max = p_min * r_best
+    pub bonus_vest_cliff: i64,        // 8 — seconds after graduation before any bonus is claimable
+    pub bonus_vest_duration: i64,     // 8 — seconds after graduation until the bonus is fully vested
+    pub fee_vest_step_count: u8,      // 1 — creator-fee milestones in use
+    pub fee_vest_schedule: [FeeVestStep; MAX_FEE_VEST_STEPS], // 13 * 8
+    pub fee_stream_duration: i64,     // 8 — > 0: creator fees stream linearly over this many seconds instead of milestones
+    pub fee_authority: Pubkey,        // 32 — holds the creator role (initial buy, fee claims); starts as `creator`
+    pub pending_fee_authority: Pubkey, // 32 — proposed fee_authority awaiting acceptance (default = none)
+    pub fee_split_count: u8,          // 1 — 0 = all creator fees go to fee_authority
+    pub fee_splits: [FeeShare; MAX_FEE_RECIPIENTS],    // 34 * 5
+    pub fee_split_claimed: [u64; MAX_FEE_RECIPIENTS],  // 8 * 5 — per-recipient lamports claimed
+    pub max_sol_per_wallet: u64,      // 8 — 0 = no limit
+    pub max_supply_bps_per_wallet: u16, // 2 — max share of token_supply one position may hold; 0 = no limit
+    pub activation_time: i64,         // 8 — time of the creator's first buy
+    pub snipe_window: i64,            // 8 — anti-sniper window after activation; 0 = off
+    pub snipe_max_buy: u64,           // 8 — max lamports per buy inside the window; 0 = no limit
+    pub snipe_fee_bps: u16,           // 2 — extra fee at activation, decaying to 0 at window end
+    pub allowlist_root: [u8; 32],     // 32 — Merkle root of (wallet, max allocation); zero = no presale
+    pub public_start_time: i64,       // 8 — end of the allowlist window (== start_time without one)
+    pub attestation_signer: Pubkey,   // 32 — key whose Ed25519 attestation buys need; default = none
+    pub quote_mint: Pubkey,           // 32 — SPL quote asset; default = native SOL
+    pub total_bonus_claimed: u64,     // 8 — bonus tokens paid out by claim_bonus
+    pub leftover_policy: LeftoverPolicy, // 9 — what happens to unsold supply and unreserved/swept bonus
+    pub leftover_released: u64,       // 8 — leftover tokens burned, listed or returned so far
+    pub bonus_claim_deadline: i64,    // 8 — seconds after graduation to claim bonus; 0 = no deadline
+    pub bonus_swept: u64,             // 8 — unclaimed bonus forfeited at the deadline
+    pub graduation_bounty_bps: u16,   // 2 — share of the vault paid to whoever graduates the launch
+    pub graduation_bounty_cap: u64,   // 8 — max bounty in quote units; 0 = bps only
+    pub graduation_fee_bps: u16,      // 2 — protocol graduation fee, fixed at creation
+    pub lp_reserve_withheld: u64,     // 8 — lp_reserve tokens kept out of the pool to offset the fee and bounty
+    pub state: LaunchState,           // 1 — lifecycle; changes only through `set_state`
+    pub graduation_bounty_paid: u64,  // 8 — bounty paid out of the vault at graduation
+    pub created_slot: u64,            // 8 — slot of `initialize_launch`; positions record it
 }
 
 impl Launch {
-    // disc=8, creator=32, token_mint=32
-    // token_supply..graduation_target (8 u64s)=64, duration=8
-    // total_base_sold..total_participants (4 u64s)=32
-    // is_graduated=1, bump=1, total_creator_fees=8, creator_fees_claimed=8
-    // milestones_unlocked=1, has_initial_buy=1, name=32, symbol=10
-    // graduation_time=8, vault_bump=1, creator_fee_vault_bump=1, pool_created=1
-    // lp_reserve=8
-    // Total = 8+32+32+8*9+8+8*4+1+1+8+8+1+1+32+10+8+1+1+1+8 = 265
-    pub const SIZE: usize = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 1 + 8 + 8 + 1 + 1 + 32 + 10 + 8 + 1 + 1 + 1 + 8;
-}
+    /// Apply a buy to the curve totals (shared by `buy` and off-chain simulation).
+    pub fn record_buy(&mut self, quote: &BuyQuote, is_new_participant: bool) -> Result<()> {
+        self.total_base_sold = self.total_base_sold
+            .checked_add(quote.base_tokens).ok_or(VestigeError::Overflow)?;
+        self.total_bonus_reserved = self.total_bonus_reserved
+            .checked_add(quote.bonus).ok_or(VestigeError::Overflow)?;
+        self.total_sol_collected = self.total_sol_collected
+            .checked_add(quote.net_amount).ok_or(VestigeError::Overflow)?;
+        self.total_creator_fees = self.total_creator_fees
+            .checked_add(quote.creator_fee).ok_or(VestigeError::Overflow)?;
+        if is_new_participant {
+            self.total_participants = self.total_participants
+                .checked_add(1).ok_or(VestigeError::Overflow)?;
+        }
+        Ok(())
+    }
+
+    /// Move the launch to `to` if its lifecycle allows it (see `LaunchState`) and return
+    /// the previous state. Leaving Pending or Active for a closed curve records
+    /// `graduation_time`; on a graduation it also starts bonus vesting and the creator-fee
+    /// schedule. Instructions go through `transition_launch`, which also emits the event.
+    pub fn set_state(&mut self, to: LaunchState, timestamp: i64) -> Result<LaunchState> {
+        require!(self.state.can_transition_to(to), VestigeError::InvalidStateTransition);
+        let from = self.state;
+        self.state = to;
+        if from.is_trading() && to.curve_closed() {
+            self.graduation_time = timestamp;
+            if to.is_settled_success() {
+                self.milestones_unlocked = milestones_at_graduation(self); // Unlock the zero-delay steps
+            }
+        }
+        // The older flags stay in step for clients that still read them
+        self.has_initial_buy |= to == LaunchState::Active;
+        self.is_graduated = to.is_settled_success();
+        self.pool_created = to == LaunchState::Graduated;
+        Ok(from)
+    }
+
+    /// Enforce the per-wallet limits on a buy against the position's totals before it.
+    pub fn check_wallet_caps(&self, position: &UserPosition, quote: &BuyQuote) -> Result<()> {
+        if self.max_sol_per_wallet > 0 {
+            let sol_spent = position.total_sol_spent
+                .checked_add(quote.sol_amount).ok_or(VestigeError::Overflow)?;
+            require!(sol_spent <= self.max_sol_per_wallet, VestigeError::WalletSolCapExceeded);
+        }
+        if self.max_supply_bps_per_wallet > 0 {
+            let max_tokens = (self.token_supply as u128)
+                .checked_mul(self.max_supply_bps_per_wallet as u128).ok_or(VestigeError::Overflow)?
+                / BPS_DENOMINATOR as u128;
+            let base_tokens = position.total_base_tokens
+                .checked_add(quote.base_tokens).ok_or(VestigeError::Overflow)?;
+            require!(base_tokens as u128 <= max_tokens, VestigeError::WalletSupplyCapExceeded);
+        }
+        Ok(())
+    }
+
+    /// Apply a sell to the curve totals. `bonus_reduction` comes from `UserPosition::record_sell`.
+    pub fn record_sell(&mut self, quote: &SellQuote, bonus_reduction: u64) -> Result<()> {
+        self.total_base_sold = self.total_base_sold
+            .checked_sub(quote.token_amount).ok_or(VestigeError::Overflow)?;
+        self.total_sol_collected = self.total_sol_collected
+            .checked_sub(quote.sol_net).ok_or(VestigeError::Overflow)?
+            .checked_sub(quote.protocol_fee).ok_or(VestigeError::Overflow)?
+            .checked_sub(quote.creator_fee).ok_or(VestigeError::Overflow)?;
+        self.total_bonus_reserved = self.total_bonus_reserved
+            .checked_sub(bonus_reduction).ok_or(VestigeError::Overflow)?;
+        self.total_creator_fees = self.total_creator_fees
+            .checked_add(quote.creator_fee).ok_or(VestigeError::Overflow)?;
+        Ok(())
+    }
+
+    // disc=8, creator=32, token_mint=32
+    // token_supply..graduation_target (8 u64s)=64, duration=8
+    // total_base_sold..total_participants (4 u64s)=32
+    // is_graduated=1, bump=1, total_creator_fees=8, creator_fees_claimed=8
+    // milestones_unlocked=1, has_initial_buy=1, name=32, symbol=10
+    // graduation_time=8, vault_bump=1, creator_fee_vault_bump=1, pool_created=1
+    // lp_reserve=8, bonus_vest_cliff=8, bonus_vest_duration=8
+    // fee_vest_step_count=1, fee_vest_schedule=FeeVestStep::SIZE*MAX_FEE_VEST_STEPS
+    // fee_stream_duration=8, fee_authority=32, pending_fee_authority=32
+    // fee_split_count=1, fee_splits=FeeShare::SIZE*MAX_FEE_RECIPIENTS, fee_split_claimed=8*MAX_FEE_RECIPIENTS
+    // max_sol_per_wallet=8, max_supply_bps_per_wallet=2
+    // activation_time=8, snipe_window=8, snipe_max_buy=8, snipe_fee_bps=2
+    // allowlist_root=32, public_start_time=8, attestation_signer=32, quote_mint=32
+    // total_bonus_claimed=8, leftover_policy=LeftoverPolicy::SIZE, leftover_released=8
+    // bonus_claim_deadline=8, bonus_swept=8, graduation_bounty_bps=2, graduation_bounty_cap=8
+    // graduation_fee_bps=2, lp_reserve_withheld=8, state=LaunchState::SIZE, graduation_bounty_paid=8
+    // created_slot=8
+    // Total = 8+32+32+8*9+8+8*4+1+1+8+8+1+1+32+10+8+1+1+1+8+8+8+1+13*8+8+32+32+1+34*5+8*5+8+2+8+8+8+2+32+8+32+32+8+9+8+8+8+2+8+2+8+1+8+8 = 887
+    pub const SIZE: usize = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 1 + 8 + 8 + 1 + 1 + 32 + 10 + 8 + 1 + 1 + 1 + 8 + 8 + 8
+        + 1 + FeeVestStep::SIZE * MAX_FEE_VEST_STEPS + 8 + 32 + 32
+        + 1 + FeeShare::SIZE * MAX_FEE_RECIPIENTS + 8 * MAX_FEE_RECIPIENTS
+        + 8 + 2
+        + 8 + 8 + 8 + 2
+        + 32 + 8
+        + 32 + 32
+        + 8 + LeftoverPolicy::SIZE + 8 + 8 + 8
+        + 2 + 8
+        + 2 + 8 + LaunchState::SIZE + 8
+        + 8;
+
+    /// The creator-fee schedule steps in use.
+    pub fn fee_vest_steps(&self) -> &[FeeVestStep] {
+        let count = (self.fee_vest_step_count as usize).min(MAX_FEE_VEST_STEPS);
+        &self.fee_vest_schedule[..count]
+    }
+
+    /// Whether the launch is quoted in an SPL token rather than SOL.
+    pub fn uses_quote_mint(&self) -> bool {
+        self.quote_mint != Pubkey::default()
+    }
+
+    /// Quote side of the launch's DEX pool: its quote mint, or WSOL for SOL launches.
+    pub fn pool_quote_mint(&self) -> Pubkey {
+        if self.uses_quote_mint() {
+            self.quote_mint
+        } else {
+            token::spl_token::native_mint::ID
+        }
+    }
+
+    /// The creator-fee split table in use (empty = everything to fee_authority).
+    pub fn fee_splits(&self) -> &[FeeShare] {
+        let count = (self.fee_split_count as usize).min(MAX_FEE_RECIPIENTS);
+        &self.fee_splits[..count]
+    }
+}
 
 #[account]
+#[derive(Default)]
 pub struct UserPosition {
     pub user: Pubkey,                 // 32
     pub launch: Pubkey,               // 32
     pub total_sol_spent: u64,         // 8
     pub total_base_tokens: u64,       // 8
     pub total_bonus_entitled: u64,    // 8
-    pub has_claimed_bonus: bool,      // 1
+    pub has_claimed_bonus: bool,      // 1 — true once the whole entitlement is claimed
     pub bump: u8,                     // 1
+    pub bonus_claimed: u64,           // 8 — bonus tokens transferred so far
+    pub launch_slot: u64,             // 8 — `Launch.created_slot` of the launch it was opened on
 }
 
 impl UserPosition {
-    // 8 (discriminator) + 32 + 32 + 8 + 8 + 8 + 1 + 1 = 98
-    pub const SIZE: usize = 8 + 32 + 32 + 8 + 8 + 8 + 1 + 1;
+    pub fn record_buy(&mut self, quote: &BuyQuote) -> Result<()> {
+        self.total_sol_spent = self.total_sol_spent
+            .checked_add(quote.sol_amount).ok_or(VestigeError::Overflow)?;
+        self.total_base_tokens = self.total_base_tokens
+            .checked_add(quote.base_tokens).ok_or(VestigeError::Overflow)?;
+        self.total_bonus_entitled = self.total_bonus_entitled
+            .checked_add(quote.bonus).ok_or(VestigeError::Overflow)?;
+        Ok(())
+    }
+
+    /// Reduce the position proportionally to `token_amount` sold.
+    /// Returns the bonus entitlement forfeited by the sale.
+    pub fn record_sell(&mut self, token_amount: u64) -> Result<u64> {
+        require!(self.total_base_tokens >= token_amount, VestigeError::InsufficientTokens);
+        let fraction_num = token_amount as u128;
+        let fraction_den = self.total_base_tokens as u128;
+
+        let sol_spent_reduction = (self.total_sol_spent as u128)
+            .checked_mul(fraction_num).ok_or(VestigeError::Overflow)?
+            .checked_div(fraction_den).ok_or(VestigeError::Overflow)? as u64;
+        let bonus_reduction = (self.total_bonus_entitled as u128)
+            .checked_mul(fraction_num).ok_or(VestigeError::Overflow)?
+            .checked_div(fraction_den).ok_or(VestigeError::Overflow)? as u64;
+
+        self.total_base_tokens = self.total_base_tokens
+            .checked_sub(token_amount).ok_or(VestigeError::Overflow)?;
+        self.total_sol_spent = self.total_sol_spent
+            .checked_sub(sol_spent_reduction).ok_or(VestigeError::Overflow)?;
+        self.total_bonus_entitled = self.total_bonus_entitled
+            .checked_sub(bonus_reduction).ok_or(VestigeError::Overflow)?;
+        Ok(bonus_reduction)
+    }
+
+    // 8 (discriminator) + 32 + 32 + 8 + 8 + 8 + 1 + 1 + 8 + 8 = 114
+    pub const SIZE: usize = 8 + 32 + 32 + 8 + 8 + 8 + 1 + 1 + 8 + 8;
+}
+
+// ============== Instruction Arguments ==============
+
+/// Optional per-launch settings passed to `initialize_launch`. `Default` gives the
+/// original behaviour (bonus fully claimable at graduation).
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct LaunchConfig {
+    /// Seconds after graduation before any bonus can be claimed.
+    pub bonus_vest_cliff: i64,
+    /// Seconds after graduation until the bonus is fully vested (linear from graduation).
+    pub bonus_vest_duration: i64,
+    /// Creator-fee milestones; empty uses `default_fee_vest_schedule`.
+    pub fee_vest_schedule: Vec<FeeVestStep>,
+    /// If > 0, creator fees stream linearly from graduation over this many seconds
+    /// instead of vesting by milestones (`fee_vest_schedule` must then be empty).
+    pub fee_stream_duration: i64,
+    /// Creator-fee recipients and shares; empty sends all fees to `fee_authority`.
+    pub fee_splits: Vec<FeeShare>,
+    /// Most lamports one wallet may spend on buys (net of sells); 0 = no limit.
+    pub max_sol_per_wallet: u64,
+    /// Most of `token_supply` (BPS) one position may hold; 0 = no limit.
+    pub max_supply_bps_per_wallet: u16,
+    /// Anti-sniper window in seconds after the creator's first buy; 0 = off.
+    pub snipe_window: i64,
+    /// Max lamports per buy inside the window; 0 = no limit.
+    pub snipe_max_buy: u64,
+    /// Extra fee (BPS of the buy) at activation, decaying linearly to 0 over the window.
+    pub snipe_fee_bps: u16,
+    /// Merkle root of allowlisted (wallet, max allocation) leaves; zero = no presale.
+    pub allowlist_root: [u8; 32],
+    /// End of the allowlist window; must fall between start_time and end_time.
+    pub public_start_time: i64,
+    /// If set, buys need this key's Ed25519 attestation (see `attestation_message`).
+    pub attestation_signer: Pubkey,
+    /// What happens to unsold supply and unreserved or swept bonus after graduation.
+    pub leftover_policy: LeftoverPolicy,
+    /// Seconds after graduation to claim bonus before `sweep_unclaimed_bonus` may forfeit
+    /// it; 0 = no deadline. Must leave MIN_BONUS_CLAIM_WINDOW after full vesting.
+    pub bonus_claim_deadline: i64,
+    /// Share of the vault (BPS, at most MAX_GRADUATION_BOUNTY_BPS) paid to whoever graduates the launch.
+    pub graduation_bounty_bps: u16,
+    /// Cap on the graduation bounty in quote units; 0 = bps only.
+    pub graduation_bounty_cap: u64,
+}
+
+/// Proof of a buyer's allowlist entry, passed to `buy` during the presale window.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AllowlistProof {
+    /// Lamports the wallet may spend during the presale.
+    pub max_allocation: u64,
+    pub proof: Vec<[u8; 32]>,
+}
+
+/// A creator-fee recipient and its share of every claim, in BPS.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FeeShare {
+    pub recipient: Pubkey,
+    pub bps: u16,
+}
+
+impl FeeShare {
+    pub const SIZE: usize = 32 + 2;
+}
+
+/// One creator-fee milestone: `bps` of the fees unlock once `delay` seconds have
+/// passed since graduation and `condition` holds. Steps with a zero delay unlock
+/// at graduation and cannot carry a condition.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FeeVestStep {
+    pub delay: i64,
+    pub bps: u16,
+    pub condition: MilestoneCondition,
+}
+
+impl FeeVestStep {
+    pub const SIZE: usize = 8 + 2 + MilestoneCondition::SIZE;
+}
+
+/// On-chain performance a milestone can require before it unlocks.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MilestoneCondition {
+    /// Time lock only.
+    #[default]
+    None,
+    /// Raydium pool spot price at least this fraction (BPS) of `p_min`.
+    MinPoolPriceBps(u16),
+    /// At least this many distinct wallets holding the token.
+    MinHolders(u16),
+}
+
+impl MilestoneCondition {
+    pub const SIZE: usize = 1 + 2; // variant tag + largest payload
+}
+
+/// Lifecycle of a launch. It only moves along `can_transition_to`, and every move
+/// emits `LaunchStateChanged`:
+///   Pending    → Active      creator's first buy
+///              → Cancelled   `cancel_launch`
+///              → Failed      `graduate` after the end time, nothing bought
+///   Active     → Graduating  target reached (`buy` or `graduate`), pool not created yet
+///              → Graduated   target reached and liquidity released for the pool
+///              → Failed      `graduate` after the end time, short of the target
+///   Graduating → Graduated   `graduate_to_dex`
+/// Graduating, Graduated and Failed close the curve: no more trading. Only a graduation
+/// (Graduating or Graduated) vests bonus and unlocks creator fees from `graduation_time`;
+/// a Failed launch instead refunds its holders.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LaunchState {
+    #[default]
+    Pending,
+    Active,
+    Graduating,
+    Graduated,
+    Failed,
+    Cancelled,
+}
+
+impl LaunchState {
+    pub const SIZE: usize = 1;
+
+    pub fn can_transition_to(self, to: LaunchState) -> bool {
+        use LaunchState::*;
+        matches!(
+            (self, to),
+            (Pending, Active | Cancelled | Failed)
+                | (Active, Graduating | Graduated | Failed)
+                | (Graduating, Graduated)
+        )
+    }
+
+    /// Buys and sells are accepted (Pending takes only the creator's activating buy).
+    pub fn is_trading(self) -> bool {
+        matches!(self, LaunchState::Pending | LaunchState::Active)
+    }
+
+    /// The curve is over, listed or not.
+    pub fn curve_closed(self) -> bool {
+        matches!(self, LaunchState::Graduating | LaunchState::Graduated | LaunchState::Failed)
+    }
+
+    /// The curve reached its target: bonus vests, creator fees unlock and milestones advance.
+    pub fn is_settled_success(self) -> bool {
+        matches!(self, LaunchState::Graduating | LaunchState::Graduated)
+    }
+}
+
+/// Disposition of leftover tokens (see `leftover_tokens`) after graduation.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LeftoverPolicy {
+    /// Burned by `release_leftover_tokens`.
+    #[default]
+    Burn,
+    /// Added to the DEX pool by `graduate_to_dex` (lists below p_min).
+    AddToLp,
+    /// Returned to `fee_authority`, vesting linearly from graduation over this many seconds.
+    VestToCreator(i64),
+}
+
+impl LeftoverPolicy {
+    pub const SIZE: usize = 1 + 8; // variant tag + largest payload
+}
+
+#[account]
+#[derive(Default)]
+pub struct ProtocolConfig {
+    pub admin: Pubkey,                // 32
+    pub referral_share_bps: u64,      // 8 — share of the protocol fee paid to referrers
+    pub bump: u8,                     // 1
+    pub graduation_fee_bps: u64,      // 8 — share of the vault sent to the treasury at listing
+}
+
+impl ProtocolConfig {
+    // 8 (discriminator) + 32 + 8 + 1 + 8 = 57
+    pub const SIZE: usize = 8 + 32 + 8 + 1 + 8;
+}
+
+/// Referral PDA for one wallet. Holds its accrued referral fees as lamports; on
+/// quote-mint launches the referral share is paid straight to the authority's token
+/// account and counted apart, in quote-token units, so the lamport claim accounting
+/// never mixes the two.
+#[account]
+#[derive(Default)]
+pub struct Referrer {
+    pub authority: Pubkey,            // 32
+    pub total_earned: u64,            // 8 — lamports, SOL launches only
+    pub total_claimed: u64,           // 8 — lamports withdrawn by `claim_referral_fees`
+    pub referred_volume: u64,         // 8 — lamports spent by referred buys on SOL launches
+    pub referred_buys: u64,           // 8 — on every launch
+    pub bump: u8,                     // 1
+    pub total_paid_direct: u64,       // 8 — quote-mint referral fees paid out at the buy, in quote units
+    pub referred_volume_direct: u64,  // 8 — quote spent by referred buys on quote-mint launches
+}
+
+impl Referrer {
+    // 8 (discriminator) + 32 + 8 + 8 + 8 + 8 + 1 + 8 + 8 = 89
+    pub const SIZE: usize = 8 + 32 + 8 + 8 + 8 + 8 + 1 + 8 + 8;
+
+    /// Count a referred buy of `volume` that earned `fee`: claimable lamports on a SOL
+    /// launch, or quote tokens already paid out (`paid_direct`) on a quote-mint launch.
+    pub fn record_referral(&mut self, fee: u64, volume: u64, paid_direct: bool) -> Result<()> {
+        let (earned, referred_volume) = if paid_direct {
+            (&mut self.total_paid_direct, &mut self.referred_volume_direct)
+        } else {
+            (&mut self.total_earned, &mut self.referred_volume)
+        };
+        *earned = earned.checked_add(fee).ok_or(VestigeError::Overflow)?;
+        *referred_volume = referred_volume.checked_add(volume).ok_or(VestigeError::Overflow)?;
+        self.referred_buys = self.referred_buys
+            .checked_add(1).ok_or(VestigeError::Overflow)?;
+        Ok(())
+    }
+}
+
+// ============== Events ==============
+// Emitted via `emit!` ("Program data: <base64>" log lines) for indexers; the
+// human-readable `msg!` lines above are kept for the existing log parsers.
+
+#[event]
+pub struct LaunchCreated {
+    pub launch: Pubkey,
+    pub creator: Pubkey,
+    pub token_mint: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub token_supply: u64,
+    pub bonus_pool: u64,
+    pub lp_reserve: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub p_max: u64,
+    pub p_min: u64,
+    pub r_best: u64,
+    pub r_min: u64,
+    pub graduation_target: u64,
+    pub bonus_vest_cliff: i64,
+    pub bonus_vest_duration: i64,
+    pub fee_vest_schedule: Vec<FeeVestStep>,
+    pub fee_stream_duration: i64,
+    pub fee_splits: Vec<FeeShare>,
+    pub max_sol_per_wallet: u64,
+    pub max_supply_bps_per_wallet: u16,
+    pub snipe_window: i64,
+    pub snipe_max_buy: u64,
+    pub snipe_fee_bps: u16,
+    pub allowlist_root: [u8; 32],
+    pub public_start_time: i64,
+    pub attestation_signer: Pubkey,
+    pub quote_mint: Pubkey,
+    pub leftover_policy: LeftoverPolicy,
+    pub bonus_claim_deadline: i64,
+    pub graduation_bounty_bps: u16,
+    pub graduation_bounty_cap: u64,
+    pub graduation_fee_bps: u16,
+}
+
+/// `curve_price` is the execution price; position_* and total_* are post-trade values.
+#[event]
+pub struct BuyEvent {
+    pub launch: Pubkey,
+    pub user: Pubkey,
+    /// Gross amount charged; `sol_refunded` is the part of the request left unfilled at a cap.
+    pub sol_amount: u64,
+    pub sol_refunded: u64,
+    pub net_amount: u64,
+    pub protocol_fee: u64,
+    pub creator_fee: u64,
+    /// Anti-sniper part of `creator_fee`.
+    pub snipe_fee: u64,
+    /// Referrer wallet (default if none) and its part of `protocol_fee`.
+    pub referrer: Pubkey,
+    pub referral_fee: u64,
+    pub base_tokens: u64,
+    pub bonus: u64,
+    pub bonus_shortfall: u64,
+    pub curve_price: u64,
+    pub position_base_tokens: u64,
+    pub position_bonus_entitled: u64,
+    pub total_base_sold: u64,
+    pub total_sol_collected: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SellEvent {
+    pub launch: Pubkey,
+    pub user: Pubkey,
+    pub token_amount: u64,
+    pub sol_gross: u64,
+    pub sol_net: u64,
+    pub protocol_fee: u64,
+    pub creator_fee: u64,
+    pub bonus_forfeited: u64,
+    pub curve_price: u64,
+    pub position_base_tokens: u64,
+    pub position_bonus_entitled: u64,
+    pub total_base_sold: u64,
+    pub total_sol_collected: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GraduationEvent {
+    pub launch: Pubkey,
+    pub total_sol_collected: u64,
+    pub total_base_sold: u64,
+    pub total_bonus_reserved: u64,
+    pub sol_released: u64,
+    pub tokens_released: u64,
+    pub pool_created: bool,
+    pub graduation_fee: u64,
+    pub bounty: u64,
+    pub bounty_recipient: Pubkey,
+    /// Creator-fee milestones unlocked at graduation (the schedule's zero-delay steps).
+    pub milestones_unlocked: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BonusClaimed {
+    pub launch: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub total_claimed: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CreatorFeesClaimed {
+    pub launch: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub milestones_unlocked: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CreatorTransferProposed {
+    pub launch: Pubkey,
+    pub fee_authority: Pubkey,
+    pub pending_fee_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CreatorTransferAccepted {
+    pub launch: Pubkey,
+    pub previous_fee_authority: Pubkey,
+    pub fee_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReferralFeesClaimed {
+    pub referrer: Pubkey,
+    pub authority: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MilestoneAdvanced {
+    pub launch: Pubkey,
+    pub milestones_unlocked: u8,
+    pub unlocked_bps: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LaunchCancelled {
+    pub launch: Pubkey,
+    pub creator: Pubkey,
+    pub tokens_returned: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LeftoverTokensReleased {
+    pub launch: Pubkey,
+    pub amount: u64,
+    pub burned: bool,
+    pub total_released: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UnclaimedBonusSwept {
+    pub launch: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LaunchStateChanged {
+    pub launch: Pubkey,
+    pub from: LaunchState,
+    pub to: LaunchState,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LaunchClosed {
+    pub launch: Pubkey,
+    pub creator: Pubkey,
+    pub tokens_burned: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RefundEvent {
+    pub launch: Pubkey,
+    pub user: Pubkey,
+    pub token_amount: u64,
+    pub refund: u64,
+    pub bonus_forfeited: u64,
+    pub position_base_tokens: u64,
+    pub position_bonus_entitled: u64,
+    pub total_base_sold: u64,
+    pub total_sol_collected: u64,
+    pub timestamp: i64,
+}
+
+// ============== Return Data ==============
+
+/// Returned by `quote_buy`. Amounts in lamports / base-token units, weight scaled by WEIGHT_PRECISION.
+/// `curve_price` is the execution price; `price_after` the curve price once the buy lands.
+/// `bonus_shortfall` is the bonus cut because the pool ran out (0 unless nearly exhausted).
+/// `snipe_fee` is the anti-sniper part of `creator_fee` (0 outside the protection window).
+/// `sol_amount` is the filled gross amount; `sol_refunded` the rest of the request, left
+/// unfilled because the supply or graduation target was reached (0 for a full fill).
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BuyQuote {
+    pub sol_amount: u64,
+    pub net_amount: u64,
+    pub protocol_fee: u64,
+    pub creator_fee: u64,
+    pub snipe_fee: u64,
+    pub base_tokens: u64,
+    pub bonus: u64,
+    pub bonus_shortfall: u64,
+    pub curve_price: u64,
+    pub price_after: u64,
+    pub weight_after_scaled: u64,
+    pub graduation_progress_bps: u64,
+    pub sol_refunded: u64,
+}
+
+/// Returned by `quote_sell`. `sol_net` is what the seller receives after fees.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SellQuote {
+    pub token_amount: u64,
+    pub sol_gross: u64,
+    pub protocol_fee: u64,
+    pub creator_fee: u64,
+    pub sol_net: u64,
+    pub curve_price: u64,
+    pub price_after: u64,
+    pub weight_after_scaled: u64,
+    pub graduation_progress_bps: u64,
+}
+
+/// Returned by `get_launch_view`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct LaunchView {
+    pub current_price: u64,
+    pub current_weight_scaled: u64,
+    pub graduation_progress_bps: u64,
+    pub total_sol_collected: u64,
+    pub graduation_target: u64,
+    pub total_base_sold: u64,
+    pub remaining_supply: u64,
+    pub total_bonus_reserved: u64,
+    pub remaining_bonus: u64,
+    pub total_participants: u64,
+    pub state: LaunchState,
+    pub has_initial_buy: bool,
+    pub is_graduated: bool,
+    pub quote_mint: Pubkey,
+    pub pool_created: bool,
+    pub milestones_unlocked: u8,
+    pub milestone_count: u8,
+    pub fee_unlocked_bps: u64,
+    pub fee_stream_duration: i64,
+    pub creator_fees_unlocked: u64,
+    pub total_creator_fees: u64,
+    pub creator_fees_claimed: u64,
 }
 
 // ============== Contexts ==============
@@ -927,7 +3411,7 @@ pub struct InitializeLaunch<'info> {
         seeds = [LAUNCH_SEED, creator.key().as_ref(), token_mint.key().as_ref()],
         bump
     )]
-    pub launch: Account<'info, Launch>,
+    pub launch: Box<Account<'info, Launch>>,
 
     /// CHECK: Vault PDA for holding SOL (program-owned, 0 data)
     #[account(
@@ -956,88 +3440,523 @@ pub struct InitializeLaunch<'info> {
     pub token_metadata_program: AccountInfo<'info>,
 
     #[account(mut)]
-    pub creator: Signer<'info>,
+    pub creator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    pub rent: Sysvar<'info, Rent>,
+
+    /// CHECK: ProtocolConfig PDA, read for the graduation fee; may be uninitialized
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump)]
+    pub protocol_config: UncheckedAccount<'info>,
+
+    /// Optional SPL quote asset (e.g. USDC); omit for a SOL launch
+    pub quote_mint: Option<Account<'info, Mint>>,
+
+    /// Required with `quote_mint` (initializes the vaults as token accounts)
+    pub token_program: Option<Program<'info, Token>>,
+}
+
+#[derive(Accounts)]
+pub struct Buy<'info> {
+    #[account(
+        mut,
+        seeds = [LAUNCH_SEED, launch.creator.as_ref(), launch.token_mint.as_ref()],
+        bump = launch.bump
+    )]
+    pub launch: Box<Account<'info, Launch>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserPosition::SIZE, // extra 8 so rent-exempt minimum is clearly met (fixes InsufficientFundsForRent on account 7)
+        seeds = [POSITION_SEED, launch.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub user_position: Account<'info, UserPosition>,
+
+    /// CHECK: SOL vault PDA
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, launch.key().as_ref()],
+        bump
+    )]
+    pub vault: AccountInfo<'info>,
+
+    /// CHECK: Creator fee vault PDA
+    #[account(
+        mut,
+        seeds = [CREATOR_FEE_VAULT_SEED, launch.key().as_ref()],
+        bump
+    )]
+    pub creator_fee_vault: AccountInfo<'info>,
+
+    /// CHECK: Protocol treasury account — must match hardcoded PROTOCOL_TREASURY
+    #[account(
+        mut,
+        constraint = protocol_treasury.key() == PROTOCOL_TREASURY @ VestigeError::Unauthorized
+    )]
+    pub protocol_treasury: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        constraint = token_vault.mint == launch.token_mint @ VestigeError::InvalidTokenVault
+    )]
+    pub token_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.owner == user.key() @ VestigeError::InvalidUserTokenAccount,
+        constraint = user_token_account.mint == launch.token_mint @ VestigeError::InvalidUserTokenAccount
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+
+    /// Required when `referrer` is passed (supplies the referral share)
+    #[account(seeds = [PROTOCOL_CONFIG_SEED], bump = protocol_config.bump)]
+    pub protocol_config: Option<Account<'info, ProtocolConfig>>,
+
+    /// Optional referrer PDA credited with part of the protocol fee
+    #[account(
+        mut,
+        seeds = [REFERRER_SEED, referrer.authority.as_ref()],
+        bump = referrer.bump
+    )]
+    pub referrer: Option<Account<'info, Referrer>>,
+
+    /// CHECK: Instructions sysvar — required when the launch has an attestation signer
+    #[account(address = ix_sysvar::ID)]
+    pub instructions: Option<UncheckedAccount<'info>>,
+
+    /// Quote-mint launches: the buyer's quote token account (pays for the buy)
+    #[account(
+        mut,
+        constraint = user_quote_account.mint == launch.quote_mint @ VestigeError::InvalidQuoteAccount,
+        constraint = user_quote_account.owner == user.key() @ VestigeError::InvalidQuoteAccount
+    )]
+    pub user_quote_account: Option<Account<'info, TokenAccount>>,
+
+    /// Quote-mint launches: the protocol treasury's quote token account
+    #[account(
+        mut,
+        constraint = treasury_quote_account.mint == launch.quote_mint @ VestigeError::InvalidQuoteAccount,
+        constraint = treasury_quote_account.owner == PROTOCOL_TREASURY @ VestigeError::InvalidQuoteAccount
+    )]
+    pub treasury_quote_account: Option<Account<'info, TokenAccount>>,
+
+    /// Quote-mint launches with a referrer: the referrer authority's quote token account
+    #[account(
+        mut,
+        constraint = referrer_quote_account.mint == launch.quote_mint @ VestigeError::InvalidQuoteAccount
+    )]
+    pub referrer_quote_account: Option<Account<'info, TokenAccount>>,
+
+    /// Graduating buy only: the buyer's token account that receives the pool tokens
+    /// (the pool quote goes to the buyer, or `user_quote_account` on quote-mint launches)
+    #[account(
+        mut,
+        constraint = pool_token_account.owner == user.key() @ VestigeError::InvalidUserTokenAccount,
+        constraint = pool_token_account.mint == launch.token_mint @ VestigeError::InvalidUserTokenAccount
+    )]
+    pub pool_token_account: Option<Account<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct Sell<'info> {
+    #[account(
+        mut,
+        seeds = [LAUNCH_SEED, launch.creator.as_ref(), launch.token_mint.as_ref()],
+        bump = launch.bump
+    )]
+    pub launch: Box<Account<'info, Launch>>,
+
+    #[account(
+        mut,
+        seeds = [POSITION_SEED, launch.key().as_ref(), user.key().as_ref()],
+        bump = user_position.bump,
+        constraint = user_position.launch == launch.key() @ VestigeError::PositionMismatch,
+        constraint = user_position.launch_slot == launch.created_slot @ VestigeError::StalePosition
+    )]
+    pub user_position: Account<'info, UserPosition>,
+
+    /// CHECK: SOL vault PDA
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, launch.key().as_ref()],
+        bump
+    )]
+    pub vault: AccountInfo<'info>,
+
+    /// CHECK: Creator fee vault PDA
+    #[account(
+        mut,
+        seeds = [CREATOR_FEE_VAULT_SEED, launch.key().as_ref()],
+        bump
+    )]
+    pub creator_fee_vault: AccountInfo<'info>,
+
+    /// CHECK: Protocol treasury account — must match hardcoded PROTOCOL_TREASURY
+    #[account(
+        mut,
+        constraint = protocol_treasury.key() == PROTOCOL_TREASURY @ VestigeError::Unauthorized
+    )]
+    pub protocol_treasury: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        constraint = token_vault.mint == launch.token_mint @ VestigeError::InvalidTokenVault
+    )]
+    pub token_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.owner == user.key() @ VestigeError::InvalidUserTokenAccount,
+        constraint = user_token_account.mint == launch.token_mint @ VestigeError::InvalidUserTokenAccount
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+    /// Quote-mint launches: the seller's quote token account (receives the payout)
+    #[account(
+        mut,
+        constraint = user_quote_account.mint == launch.quote_mint @ VestigeError::InvalidQuoteAccount,
+        constraint = user_quote_account.owner == user.key() @ VestigeError::InvalidQuoteAccount
+    )]
+    pub user_quote_account: Option<Account<'info, TokenAccount>>,
+
+    /// Quote-mint launches: the protocol treasury's quote token account
+    #[account(
+        mut,
+        constraint = treasury_quote_account.mint == launch.quote_mint @ VestigeError::InvalidQuoteAccount,
+        constraint = treasury_quote_account.owner == PROTOCOL_TREASURY @ VestigeError::InvalidQuoteAccount
+    )]
+    pub treasury_quote_account: Option<Account<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct Graduate<'info> {
+    #[account(mut)]
+    pub launch: Box<Account<'info, Launch>>,
+
+    /// CHECK: Vault PDA — pays the graduation bounty
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, launch.key().as_ref()],
+        bump
+    )]
+    pub vault: AccountInfo<'info>,
+
+    /// Caller; receives the graduation bounty
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Quote-mint launches: the caller's quote token account for the bounty
+    #[account(
+        mut,
+        constraint = authority_quote_account.mint == launch.quote_mint @ VestigeError::InvalidQuoteAccount,
+        constraint = authority_quote_account.owner == authority.key() @ VestigeError::InvalidQuoteAccount
+    )]
+    pub authority_quote_account: Option<Account<'info, TokenAccount>>,
+
+    /// Required with `authority_quote_account`
+    pub token_program: Option<Program<'info, Token>>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimBonus<'info> {
+    #[account(
+        mut,
+        seeds = [LAUNCH_SEED, launch.creator.as_ref(), launch.token_mint.as_ref()],
+        bump = launch.bump
+    )]
+    pub launch: Box<Account<'info, Launch>>,
+
+    #[account(
+        mut,
+        seeds = [POSITION_SEED, launch.key().as_ref(), user.key().as_ref()],
+        bump = user_position.bump,
+        constraint = user_position.launch == launch.key() @ VestigeError::PositionMismatch,
+        constraint = user_position.launch_slot == launch.created_slot @ VestigeError::StalePosition
+    )]
+    pub user_position: Account<'info, UserPosition>,
+
+    #[account(
+        mut,
+        constraint = token_vault.mint == launch.token_mint @ VestigeError::InvalidTokenVault
+    )]
+    pub token_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.owner == user.key() @ VestigeError::InvalidUserTokenAccount,
+        constraint = user_token_account.mint == launch.token_mint @ VestigeError::InvalidUserTokenAccount
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+
+    pub user: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CreatorClaimFees<'info> {
+    #[account(
+        mut,
+        seeds = [LAUNCH_SEED, launch.creator.as_ref(), launch.token_mint.as_ref()],
+        bump = launch.bump
+    )]
+    pub launch: Box<Account<'info, Launch>>,
+
+    /// CHECK: Creator fee vault PDA holding accumulated creator fees
+    #[account(
+        mut,
+        seeds = [CREATOR_FEE_VAULT_SEED, launch.key().as_ref()],
+        bump
+    )]
+    pub creator_fee_vault: AccountInfo<'info>,
+
+    /// The launch's `fee_authority`, or one of its fee split recipients
+    #[account(mut)]
+    pub recipient: Signer<'info>,
+
+    /// Quote-mint launches: the recipient's quote token account
+    #[account(
+        mut,
+        constraint = recipient_quote_account.mint == launch.quote_mint @ VestigeError::InvalidQuoteAccount,
+        constraint = recipient_quote_account.owner == recipient.key() @ VestigeError::InvalidQuoteAccount
+    )]
+    pub recipient_quote_account: Option<Account<'info, TokenAccount>>,
+
+    /// Required for quote-mint launches
+    pub token_program: Option<Program<'info, Token>>,
+}
+
+/// Remaining accounts: whatever the next milestone's condition needs
+/// (see `check_milestone_condition`); none for time-only milestones.
+#[derive(Accounts)]
+pub struct AdvanceMilestone<'info> {
+    #[account(
+        mut,
+        seeds = [LAUNCH_SEED, launch.creator.as_ref(), launch.token_mint.as_ref()],
+        bump = launch.bump
+    )]
+    pub launch: Box<Account<'info, Launch>>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeCreatorTransfer<'info> {
+    #[account(
+        mut,
+        seeds = [LAUNCH_SEED, launch.creator.as_ref(), launch.token_mint.as_ref()],
+        bump = launch.bump
+    )]
+    pub launch: Box<Account<'info, Launch>>,
+
+    pub fee_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptCreatorTransfer<'info> {
+    #[account(
+        mut,
+        seeds = [LAUNCH_SEED, launch.creator.as_ref(), launch.token_mint.as_ref()],
+        bump = launch.bump
+    )]
+    pub launch: Box<Account<'info, Launch>>,
+
+    pub new_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeProtocolConfig<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = ProtocolConfig::SIZE,
+        seeds = [PROTOCOL_CONFIG_SEED],
+        bump
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        mut,
+        constraint = admin.key() == PROTOCOL_TREASURY @ VestigeError::Unauthorized
+    )]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateProtocolConfig<'info> {
+    #[account(
+        mut,
+        seeds = [PROTOCOL_CONFIG_SEED],
+        bump = protocol_config.bump,
+        has_one = admin @ VestigeError::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterReferrer<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = Referrer::SIZE,
+        seeds = [REFERRER_SEED, authority.key().as_ref()],
+        bump
+    )]
+    pub referrer: Account<'info, Referrer>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimReferralFees<'info> {
+    #[account(
+        mut,
+        seeds = [REFERRER_SEED, authority.key().as_ref()],
+        bump = referrer.bump,
+        has_one = authority @ VestigeError::Unauthorized
+    )]
+    pub referrer: Account<'info, Referrer>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct GraduateToDex<'info> {
+    #[account(
+        mut,
+        seeds = [LAUNCH_SEED, launch.creator.as_ref(), launch.token_mint.as_ref()],
+        bump = launch.bump
+    )]
+    pub launch: Box<Account<'info, Launch>>,
+
+    /// CHECK: SOL vault PDA (program-owned, holds collected lamports)
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, launch.key().as_ref()],
+        bump
+    )]
+    pub vault: AccountInfo<'info>,
+
+    /// Launch's token ATA — tokens transferred to payer_token_account
+    #[account(
+        mut,
+        constraint = token_vault.mint == launch.token_mint @ VestigeError::InvalidTokenVault,
+        constraint = token_vault.owner == launch.key() @ VestigeError::InvalidTokenVault,
+    )]
+    pub token_vault: Account<'info, TokenAccount>,
+
+    /// Pays for pool creation rent; receives released SOL
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Payer's token ATA — receives pool tokens
+    #[account(mut)]
+    pub payer_token_account: AccountInfo<'info>,
 
+    pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 
-    pub rent: Sysvar<'info, Rent>,
-}
-
-#[derive(Accounts)]
-pub struct Buy<'info> {
+    /// Quote-mint launches: the payer's quote token account (receives the vault's quote)
     #[account(
         mut,
-        seeds = [LAUNCH_SEED, launch.creator.as_ref(), launch.token_mint.as_ref()],
-        bump = launch.bump
+        constraint = payer_quote_account.mint == launch.quote_mint @ VestigeError::InvalidQuoteAccount
     )]
-    pub launch: Account<'info, Launch>,
+    pub payer_quote_account: Option<Account<'info, TokenAccount>>,
 
+    /// CHECK: Protocol treasury account — receives the graduation fee
     #[account(
-        init_if_needed,
-        payer = user,
-        space = 8 + UserPosition::SIZE, // extra 8 so rent-exempt minimum is clearly met (fixes InsufficientFundsForRent on account 7)
-        seeds = [POSITION_SEED, launch.key().as_ref(), user.key().as_ref()],
-        bump
+        mut,
+        constraint = protocol_treasury.key() == PROTOCOL_TREASURY @ VestigeError::Unauthorized
     )]
-    pub user_position: Account<'info, UserPosition>,
+    pub protocol_treasury: AccountInfo<'info>,
 
-    /// CHECK: SOL vault PDA
+    /// Quote-mint launches with a graduation fee: the protocol treasury's quote token account
     #[account(
         mut,
-        seeds = [VAULT_SEED, launch.key().as_ref()],
-        bump
+        constraint = treasury_quote_account.mint == launch.quote_mint @ VestigeError::InvalidQuoteAccount,
+        constraint = treasury_quote_account.owner == PROTOCOL_TREASURY @ VestigeError::InvalidQuoteAccount
     )]
-    pub vault: AccountInfo<'info>,
+    pub treasury_quote_account: Option<Account<'info, TokenAccount>>,
+}
 
-    /// CHECK: Creator fee vault PDA
+#[derive(Accounts)]
+pub struct ReleaseLeftoverTokens<'info> {
     #[account(
         mut,
-        seeds = [CREATOR_FEE_VAULT_SEED, launch.key().as_ref()],
-        bump
+        seeds = [LAUNCH_SEED, launch.creator.as_ref(), launch.token_mint.as_ref()],
+        bump = launch.bump,
+        has_one = token_mint @ VestigeError::InvalidTokenVault
     )]
-    pub creator_fee_vault: AccountInfo<'info>,
+    pub launch: Box<Account<'info, Launch>>,
 
-    /// CHECK: Protocol treasury account — must match hardcoded PROTOCOL_TREASURY
     #[account(
         mut,
-        constraint = protocol_treasury.key() == PROTOCOL_TREASURY @ VestigeError::Unauthorized
+        constraint = token_vault.mint == launch.token_mint @ VestigeError::InvalidTokenVault,
+        constraint = token_vault.owner == launch.key() @ VestigeError::InvalidTokenVault,
     )]
-    pub protocol_treasury: AccountInfo<'info>,
+    pub token_vault: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub token_mint: Account<'info, Mint>,
+
+    pub token_program: Program<'info, Token>,
 
+    /// VestToCreator launches: `fee_authority`'s token account
     #[account(
         mut,
-        constraint = token_vault.mint == launch.token_mint @ VestigeError::InvalidTokenVault
+        constraint = creator_token_account.owner == launch.fee_authority @ VestigeError::InvalidUserTokenAccount,
+        constraint = creator_token_account.mint == launch.token_mint @ VestigeError::InvalidUserTokenAccount
     )]
-    pub token_vault: Account<'info, TokenAccount>,
+    pub creator_token_account: Option<Account<'info, TokenAccount>>,
+}
 
+#[derive(Accounts)]
+pub struct SweepUnclaimedBonus<'info> {
     #[account(
         mut,
-        constraint = user_token_account.owner == user.key() @ VestigeError::InvalidUserTokenAccount,
-        constraint = user_token_account.mint == launch.token_mint @ VestigeError::InvalidUserTokenAccount
+        seeds = [LAUNCH_SEED, launch.creator.as_ref(), launch.token_mint.as_ref()],
+        bump = launch.bump
     )]
-    pub user_token_account: Account<'info, TokenAccount>,
-
-    #[account(mut)]
-    pub user: Signer<'info>,
-
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
+    pub launch: Box<Account<'info, Launch>>,
 }
 
 #[derive(Accounts)]
-pub struct Sell<'info> {
+pub struct Refund<'info> {
     #[account(
         mut,
         seeds = [LAUNCH_SEED, launch.creator.as_ref(), launch.token_mint.as_ref()],
         bump = launch.bump
     )]
-    pub launch: Account<'info, Launch>,
+    pub launch: Box<Account<'info, Launch>>,
 
     #[account(
         mut,
         seeds = [POSITION_SEED, launch.key().as_ref(), user.key().as_ref()],
         bump = user_position.bump,
-        constraint = user_position.launch == launch.key() @ VestigeError::PositionMismatch
+        constraint = user_position.launch == launch.key() @ VestigeError::PositionMismatch,
+        constraint = user_position.launch_slot == launch.created_slot @ VestigeError::StalePosition
     )]
     pub user_position: Account<'info, UserPosition>,
 
@@ -1049,7 +3968,7 @@ pub struct Sell<'info> {
     )]
     pub vault: AccountInfo<'info>,
 
-    /// CHECK: Creator fee vault PDA
+    /// CHECK: Creator fee vault PDA (its fees are refunded too)
     #[account(
         mut,
         seeds = [CREATOR_FEE_VAULT_SEED, launch.key().as_ref()],
@@ -1057,16 +3976,10 @@ pub struct Sell<'info> {
     )]
     pub creator_fee_vault: AccountInfo<'info>,
 
-    /// CHECK: Protocol treasury account — must match hardcoded PROTOCOL_TREASURY
-    #[account(
-        mut,
-        constraint = protocol_treasury.key() == PROTOCOL_TREASURY @ VestigeError::Unauthorized
-    )]
-    pub protocol_treasury: AccountInfo<'info>,
-
     #[account(
         mut,
-        constraint = token_vault.mint == launch.token_mint @ VestigeError::InvalidTokenVault
+        constraint = token_vault.mint == launch.token_mint @ VestigeError::InvalidTokenVault,
+        constraint = token_vault.owner == launch.key() @ VestigeError::InvalidTokenVault,
     )]
     pub token_vault: Account<'info, TokenAccount>,
 
@@ -1081,101 +3994,121 @@ pub struct Sell<'info> {
     pub user: Signer<'info>,
 
     pub token_program: Program<'info, Token>,
-}
-
-#[derive(Accounts)]
-pub struct Graduate<'info> {
-    #[account(mut)]
-    pub launch: Account<'info, Launch>,
 
-    pub authority: Signer<'info>,
+    /// Quote-mint launches: the holder's quote token account (receives the refund)
+    #[account(
+        mut,
+        constraint = user_quote_account.mint == launch.quote_mint @ VestigeError::InvalidQuoteAccount,
+        constraint = user_quote_account.owner == user.key() @ VestigeError::InvalidQuoteAccount
+    )]
+    pub user_quote_account: Option<Account<'info, TokenAccount>>,
 }
 
 #[derive(Accounts)]
-pub struct ClaimBonus<'info> {
+pub struct CancelLaunch<'info> {
     #[account(
+        mut,
         seeds = [LAUNCH_SEED, launch.creator.as_ref(), launch.token_mint.as_ref()],
-        bump = launch.bump
+        bump = launch.bump,
+        has_one = fee_authority @ VestigeError::Unauthorized,
+        close = fee_authority
     )]
-    pub launch: Account<'info, Launch>,
+    pub launch: Box<Account<'info, Launch>>,
 
+    /// CHECK: Vault PDA (closed)
     #[account(
         mut,
-        seeds = [POSITION_SEED, launch.key().as_ref(), user.key().as_ref()],
-        bump = user_position.bump,
-        constraint = user_position.launch == launch.key() @ VestigeError::PositionMismatch
+        seeds = [VAULT_SEED, launch.key().as_ref()],
+        bump
     )]
-    pub user_position: Account<'info, UserPosition>,
+    pub vault: AccountInfo<'info>,
 
+    /// CHECK: Creator fee vault PDA (closed)
     #[account(
         mut,
-        constraint = token_vault.mint == launch.token_mint @ VestigeError::InvalidTokenVault
+        seeds = [CREATOR_FEE_VAULT_SEED, launch.key().as_ref()],
+        bump
+    )]
+    pub creator_fee_vault: AccountInfo<'info>,
+
+    /// Launch's token ATA — emptied into creator_token_account and closed
+    #[account(
+        mut,
+        constraint = token_vault.mint == launch.token_mint @ VestigeError::InvalidTokenVault,
+        constraint = token_vault.owner == launch.key() @ VestigeError::InvalidTokenVault,
     )]
     pub token_vault: Account<'info, TokenAccount>,
 
+    /// `fee_authority`'s token account
     #[account(
         mut,
-        constraint = user_token_account.owner == user.key() @ VestigeError::InvalidUserTokenAccount,
-        constraint = user_token_account.mint == launch.token_mint @ VestigeError::InvalidUserTokenAccount
+        constraint = creator_token_account.owner == fee_authority.key() @ VestigeError::InvalidUserTokenAccount,
+        constraint = creator_token_account.mint == launch.token_mint @ VestigeError::InvalidUserTokenAccount
     )]
-    pub user_token_account: Account<'info, TokenAccount>,
+    pub creator_token_account: Account<'info, TokenAccount>,
 
-    pub user: Signer<'info>,
+    #[account(mut)]
+    pub fee_authority: Signer<'info>,
 
     pub token_program: Program<'info, Token>,
-}
 
-#[derive(Accounts)]
-pub struct CreatorClaimFees<'info> {
+    /// Quote-mint launches: receives any quote tokens sent to the vaults
     #[account(
         mut,
-        seeds = [LAUNCH_SEED, launch.creator.as_ref(), launch.token_mint.as_ref()],
-        bump = launch.bump
+        constraint = creator_quote_account.mint == launch.quote_mint @ VestigeError::InvalidQuoteAccount,
+        constraint = creator_quote_account.owner == fee_authority.key() @ VestigeError::InvalidQuoteAccount
     )]
-    pub launch: Account<'info, Launch>,
+    pub creator_quote_account: Option<Account<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct ClosePosition<'info> {
+    /// CHECK: The position's launch, tied to it by the position's seeds. May already be
+    /// closed by `close_launch`; deserialized and checked in the handler otherwise.
+    pub launch: AccountInfo<'info>,
 
-    /// CHECK: Creator fee vault PDA holding accumulated creator fees
     #[account(
         mut,
-        seeds = [CREATOR_FEE_VAULT_SEED, launch.key().as_ref()],
-        bump
+        seeds = [POSITION_SEED, launch.key().as_ref(), user.key().as_ref()],
+        bump = user_position.bump,
+        constraint = user_position.launch == launch.key() @ VestigeError::PositionMismatch,
+        close = user
     )]
-    pub creator_fee_vault: AccountInfo<'info>,
+    pub user_position: Account<'info, UserPosition>,
 
     #[account(mut)]
-    pub creator: Signer<'info>,
+    pub user: Signer<'info>,
 }
 
 #[derive(Accounts)]
-pub struct AdvanceMilestone<'info> {
+pub struct CloseLaunch<'info> {
     #[account(
         mut,
         seeds = [LAUNCH_SEED, launch.creator.as_ref(), launch.token_mint.as_ref()],
-        bump = launch.bump
+        bump = launch.bump,
+        has_one = fee_authority @ VestigeError::Unauthorized,
+        has_one = token_mint @ VestigeError::InvalidTokenVault,
+        close = fee_authority
     )]
-    pub launch: Account<'info, Launch>,
-
-    pub creator: Signer<'info>,
-}
+    pub launch: Box<Account<'info, Launch>>,
 
-#[derive(Accounts)]
-pub struct GraduateToDex<'info> {
+    /// CHECK: Vault PDA (closed)
     #[account(
         mut,
-        seeds = [LAUNCH_SEED, launch.creator.as_ref(), launch.token_mint.as_ref()],
-        bump = launch.bump
+        seeds = [VAULT_SEED, launch.key().as_ref()],
+        bump
     )]
-    pub launch: Account<'info, Launch>,
+    pub vault: AccountInfo<'info>,
 
-    /// CHECK: SOL vault PDA (program-owned, holds collected lamports)
+    /// CHECK: Creator fee vault PDA (closed)
     #[account(
         mut,
-        seeds = [VAULT_SEED, launch.key().as_ref()],
+        seeds = [CREATOR_FEE_VAULT_SEED, launch.key().as_ref()],
         bump
     )]
-    pub vault: AccountInfo<'info>,
+    pub creator_fee_vault: AccountInfo<'info>,
 
-    /// Launch's token ATA — tokens transferred to payer_token_account
+    /// Launch's token ATA — remaining tokens burned, then closed
     #[account(
         mut,
         constraint = token_vault.mint == launch.token_mint @ VestigeError::InvalidTokenVault,
@@ -1183,16 +4116,30 @@ pub struct GraduateToDex<'info> {
     )]
     pub token_vault: Account<'info, TokenAccount>,
 
-    /// Pays for pool creation rent; receives released SOL
     #[account(mut)]
-    pub payer: Signer<'info>,
+    pub token_mint: Account<'info, Mint>,
 
-    /// CHECK: Payer's token ATA — receives pool tokens
     #[account(mut)]
-    pub payer_token_account: AccountInfo<'info>,
+    pub fee_authority: Signer<'info>,
 
     pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
+
+    /// Quote-mint launches: receives creator-fee dust left in the fee vault
+    #[account(
+        mut,
+        constraint = creator_quote_account.mint == launch.quote_mint @ VestigeError::InvalidQuoteAccount,
+        constraint = creator_quote_account.owner == fee_authority.key() @ VestigeError::InvalidQuoteAccount
+    )]
+    pub creator_quote_account: Option<Account<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct ViewLaunch<'info> {
+    #[account(
+        seeds = [LAUNCH_SEED, launch.creator.as_ref(), launch.token_mint.as_ref()],
+        bump = launch.bump
+    )]
+    pub launch: Box<Account<'info, Launch>>,
 }
 
 // ============== Errors ==============
@@ -1279,4 +4226,609 @@ pub enum VestigeError {
     PoolAlreadyCreated,
     #[msg("Insufficient tokens or SOL available for pool creation")]
     InsufficientPoolLiquidity,
+    #[msg("Bonus pool cannot cover the worst-case bonus (token_supply at r_best)")]
+    BonusPoolTooSmall,
+    #[msg("Vesting cliff and duration must not be negative")]
+    InvalidVestingSchedule,
+    #[msg("No bonus tokens have vested since the last claim")]
+    BonusNotYetVested,
+    #[msg("Creator-fee schedule is outside protocol bounds")]
+    InvalidFeeVestSchedule,
+    #[msg("Milestone performance condition not met")]
+    MilestoneConditionNotMet,
+    #[msg("Accounts supplied for the milestone condition are invalid")]
+    InvalidMilestoneAccounts,
+    #[msg("Creator fees stream continuously for this launch; there are no milestones")]
+    FeesStreamed,
+    #[msg("Proposed creator is already the fee authority")]
+    InvalidCreatorTransfer,
+    #[msg("No creator transfer is pending")]
+    NoPendingCreatorTransfer,
+    #[msg("Fee splits must have distinct recipients with shares summing to 100%")]
+    InvalidFeeSplits,
+    #[msg("Referral share or graduation fee exceeds the protocol maximum")]
+    InvalidProtocolConfig,
+    #[msg("Buyer cannot refer themselves")]
+    SelfReferral,
+    #[msg("Protocol config account is required for referral buys")]
+    MissingProtocolConfig,
+    #[msg("Per-wallet supply cap must not exceed 100%")]
+    InvalidWalletCap,
+    #[msg("Buy exceeds this launch's per-wallet SOL limit")]
+    WalletSolCapExceeded,
+    #[msg("Buy exceeds this launch's per-wallet share of supply")]
+    WalletSupplyCapExceeded,
+    #[msg("Anti-sniper window or fee exceeds protocol bounds")]
+    InvalidSnipeProtection,
+    #[msg("Buy exceeds the anti-sniper limit for this launch's protection window")]
+    SnipeBuyTooLarge,
+    #[msg("Allowlist window must end between start_time and end_time")]
+    InvalidAllowlistConfig,
+    #[msg("Presale buys require an allowlist proof")]
+    AllowlistProofRequired,
+    #[msg("Invalid allowlist proof")]
+    InvalidAllowlistProof,
+    #[msg("Buy exceeds the wallet's allowlist allocation")]
+    AllowlistAllocationExceeded,
+    #[msg("This launch requires a buyer attestation instruction before the buy")]
+    AttestationRequired,
+    #[msg("Attestation is not signed by this launch's signer or does not match the buy")]
+    InvalidAttestation,
+    #[msg("Attestation has expired")]
+    AttestationExpired,
+    #[msg("Buy exceeds the attested maximum amount")]
+    AttestationAmountExceeded,
+    #[msg("This launch is quoted in an SPL token; pass its quote token accounts")]
+    MissingQuoteAccount,
+    #[msg("Quote token account has the wrong mint or owner")]
+    InvalidQuoteAccount,
+    #[msg("Launch already has its initial buy")]
+    LaunchAlreadyActive,
+    #[msg("Position still holds base tokens or unclaimed bonus")]
+    PositionNotSettled,
+    #[msg("Launch still has a pool to create, bonus to claim or creator fees to vest or claim")]
+    LaunchNotSettled,
+    #[msg("Leftover vesting must be between 1 second and 2 years")]
+    InvalidLeftoverPolicy,
+    #[msg("Bonus claim deadline must leave at least 30 days after full vesting")]
+    InvalidBonusClaimDeadline,
+    #[msg("Leftover tokens are reserved for the DEX pool")]
+    LeftoverReservedForLp,
+    #[msg("Bonus claim deadline has passed")]
+    BonusClaimExpired,
+    #[msg("Bonus claim deadline has not passed yet")]
+    BonusClaimWindowOpen,
+    #[msg("Graduation bounty exceeds the protocol maximum")]
+    InvalidGraduationBounty,
+    #[msg("Graduation target already reached")]
+    GraduationTargetReached,
+    #[msg("Launch cannot move to that state from its current one")]
+    InvalidStateTransition,
+    #[msg("Refunds are only available on a failed launch")]
+    LaunchNotFailed,
+    #[msg("Positions that claimed bonus tokens cannot be refunded")]
+    BonusAlreadyClaimed,
+    #[msg("Position belongs to an earlier launch closed at this address")]
+    StalePosition,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wallet(n: u8) -> Pubkey {
+        Pubkey::new_from_array([n; 32])
+    }
+
+    // ---- allowlist Merkle tree ----
+
+    #[test]
+    fn allowlist_leaf_binds_wallet_and_allocation() {
+        let leaf = allowlist_leaf(&wallet(1), 5_000);
+        assert_ne!(leaf, allowlist_leaf(&wallet(1), 5_001));
+        assert_ne!(leaf, allowlist_leaf(&wallet(2), 5_000));
+    }
+
+    #[test]
+    fn allowlist_node_is_order_independent_and_domain_separated() {
+        let a = allowlist_leaf(&wallet(1), 1);
+        let b = allowlist_leaf(&wallet(2), 2);
+        assert_eq!(allowlist_node(&a, &b), allowlist_node(&b, &a));
+        // A node never collides with a leaf hash of the same bytes
+        assert_ne!(allowlist_node(&a, &b), hashv(&[&[0x00][..], &a, &b]).to_bytes());
+    }
+
+    #[test]
+    fn allowlist_proof_verifies_every_leaf_of_an_odd_tree() {
+        let leaves: Vec<[u8; 32]> = (1..=3).map(|n| allowlist_leaf(&wallet(n), n as u64 * 100)).collect();
+        let ab = allowlist_node(&leaves[0], &leaves[1]);
+        let root = allowlist_node(&ab, &leaves[2]);
+
+        assert!(verify_allowlist_proof(&root, leaves[0], &[leaves[1], leaves[2]]));
+        assert!(verify_allowlist_proof(&root, leaves[1], &[leaves[0], leaves[2]]));
+        assert!(verify_allowlist_proof(&root, leaves[2], &[ab]));
+    }
+
+    #[test]
+    fn allowlist_proof_rejects_wrong_allocation_or_proof() {
+        let a = allowlist_leaf(&wallet(1), 100);
+        let b = allowlist_leaf(&wallet(2), 200);
+        let root = allowlist_node(&a, &b);
+
+        assert!(!verify_allowlist_proof(&root, allowlist_leaf(&wallet(1), 101), &[b]));
+        assert!(!verify_allowlist_proof(&root, a, &[]));
+        assert!(!verify_allowlist_proof(&root, a, &[a]));
+    }
+
+    // ---- Ed25519 attestations ----
+
+    /// Ed25519 program instruction data as the SDK lays it out: header, offsets,
+    /// public key, signature, message — all referring to this instruction.
+    fn ed25519_data(signer: &Pubkey, message: &[u8], instruction_index: u16) -> Vec<u8> {
+        let (pubkey_offset, signature_offset) = (16u16, 48u16);
+        let message_offset = signature_offset + 64;
+        let mut data = vec![1u8, 0];
+        for field in [
+            signature_offset,
+            instruction_index,
+            pubkey_offset,
+            instruction_index,
+            message_offset,
+            message.len() as u16,
+            instruction_index,
+        ] {
+            data.extend_from_slice(&field.to_le_bytes());
+        }
+        data.extend_from_slice(signer.as_ref());
+        data.extend_from_slice(&[7u8; 64]);
+        data.extend_from_slice(message);
+        data
+    }
+
+    #[test]
+    fn attestation_message_layout() {
+        let message = attestation_message(&wallet(1), &wallet(2), 1_700_000_000, 42);
+        assert_eq!(&message[..32], wallet(1).as_ref());
+        assert_eq!(&message[32..64], wallet(2).as_ref());
+        assert_eq!(i64::from_le_bytes(message[64..72].try_into().unwrap()), 1_700_000_000);
+        assert_eq!(u64::from_le_bytes(message[72..].try_into().unwrap()), 42);
+    }
+
+    #[test]
+    fn parse_ed25519_instruction_reads_signer_and_message() {
+        let message = attestation_message(&wallet(1), &wallet(2), 10, 20);
+        let data = ed25519_data(&wallet(9), &message, u16::MAX);
+        let (signer, parsed) = parse_ed25519_instruction(&data).unwrap();
+        assert_eq!(signer, wallet(9));
+        assert_eq!(parsed, &message[..]);
+    }
+
+    #[test]
+    fn parse_ed25519_instruction_rejects_foreign_or_malformed_data() {
+        let message = attestation_message(&wallet(1), &wallet(2), 10, 20);
+        // Offsets pointing at another instruction
+        assert!(parse_ed25519_instruction(&ed25519_data(&wallet(9), &message, 0)).is_none());
+        // More than one signature
+        let mut data = ed25519_data(&wallet(9), &message, u16::MAX);
+        data[0] = 2;
+        assert!(parse_ed25519_instruction(&data).is_none());
+        // Message running past the end
+        let data = ed25519_data(&wallet(9), &message, u16::MAX);
+        assert!(parse_ed25519_instruction(&data[..data.len() - 1]).is_none());
+        // Too short for the header
+        assert!(parse_ed25519_instruction(&[1, 0, 0]).is_none());
+    }
+
+    // ---- partial fills ----
+
+    const SOL: u64 = 1_000_000_000;
+
+    /// An active launch: 1,000 tokens from 0.01 down to 0.001 SOL, 10 SOL target.
+    fn active_launch() -> Launch {
+        Launch {
+            token_supply: 1_000 * TOKEN_PRECISION as u64,
+            bonus_pool: 2_000 * TOKEN_PRECISION as u64,
+            p_max: 10_000_000,
+            p_min: 1_000_000,
+            r_best: 3,
+            r_min: 1,
+            graduation_target: 10 * SOL,
+            state: LaunchState::Active,
+            ..Default::default()
+        }
+    }
+
+    /// Check `quote`, made at `now`, filled as much of `requested` as the net cap allows.
+    fn assert_fill_at_cap(launch: &Launch, now: i64, quote: &BuyQuote, requested: u64, net_cap: u64) {
+        assert!(quote.net_amount <= net_cap);
+        assert_eq!(quote.sol_amount + quote.sol_refunded, requested);
+        if quote.sol_refunded > 0 {
+            let (_, _, _, next_net) = buy_fees(launch, quote.sol_amount + 1, now).unwrap();
+            assert!(next_net > net_cap);
+        }
+    }
+
+    #[test]
+    fn buy_within_caps_fills_in_full() {
+        let launch = active_launch();
+        let quote = compute_buy(&launch, SOL, 0).unwrap();
+        assert_eq!(quote.sol_amount, SOL);
+        assert_eq!(quote.sol_refunded, 0);
+        assert_eq!(quote.net_amount + quote.protocol_fee + quote.creator_fee, SOL);
+    }
+
+    #[test]
+    fn partial_fill_never_passes_the_graduation_target() {
+        let mut launch = active_launch();
+        for remaining in [1, 2, 3, 99, 101, 12_345, 999_999, 333_333_333] {
+            launch.total_sol_collected = launch.graduation_target - remaining;
+            for requested in [2 * remaining + 7, SOL, 3 * SOL + 1] {
+                let quote = compute_buy(&launch, requested, 0).unwrap();
+                assert_fill_at_cap(&launch, 0, &quote, requested, remaining);
+            }
+        }
+    }
+
+    #[test]
+    fn partial_fill_never_passes_the_token_supply() {
+        let mut launch = active_launch();
+        for remaining in [1_000, 7_777, 123_456_789, TOKEN_PRECISION as u64] {
+            launch.total_base_sold = launch.token_supply - remaining;
+            let quote = compute_buy(&launch, 5 * SOL, 0).unwrap();
+            assert!(quote.base_tokens <= remaining);
+            assert!(quote.sol_refunded > 0);
+            let price = get_curve_price(&launch, launch.total_base_sold) as u128;
+            let supply_net = (remaining as u128 * price).div_ceil(TOKEN_PRECISION) as u64;
+            assert_fill_at_cap(&launch, 0, &quote, 5 * SOL, supply_net);
+        }
+    }
+
+    #[test]
+    fn snipe_cap_applies_to_the_filled_amount() {
+        let mut launch = active_launch();
+        launch.snipe_window = 60;
+        launch.snipe_max_buy = SOL;
+        launch.snipe_fee_bps = 500;
+        assert_eq!(
+            compute_buy(&launch, 2 * SOL, 10).unwrap_err(),
+            VestigeError::SnipeBuyTooLarge.into()
+        );
+        // Filled only up to the target, so under the cap
+        launch.total_sol_collected = launch.graduation_target - SOL / 2;
+        let quote = compute_buy(&launch, 2 * SOL, 10).unwrap();
+        assert!(quote.sol_amount <= SOL);
+        assert!(quote.snipe_fee > 0);
+        assert_fill_at_cap(&launch, 10, &quote, 2 * SOL, SOL / 2);
+        // Past the window the cap is gone
+        launch.total_sol_collected = 0;
+        assert!(compute_buy(&launch, 2 * SOL, 60).is_ok());
+    }
+
+    // ---- failed launch refunds ----
+
+    #[test]
+    fn failed_launch_refunds_drain_the_vault_pro_rata() {
+        let mut launch = active_launch();
+        launch.total_base_sold = 3_000;
+        let mut vault = 1_000_001;
+        let mut refunded = 0;
+        for tokens in [1_000, 1_000, 1_000] {
+            let refund = failed_launch_refund(&launch, vault, tokens).unwrap();
+            assert!(refund >= 333_333);
+            launch.total_base_sold -= tokens;
+            vault -= refund;
+            refunded += refund;
+        }
+        assert_eq!((vault, refunded), (0, 1_000_001));
+        assert!(failed_launch_refund(&launch, vault, 1).is_err());
+    }
+
+    #[test]
+    fn failed_launch_positions_close_only_once_refunded() {
+        let mut launch = active_launch();
+        launch.state = LaunchState::Failed;
+        let mut position = UserPosition {
+            total_base_tokens: 1_000,
+            total_bonus_entitled: 500,
+            ..Default::default()
+        };
+        // Its tokens still count in total_base_sold, so closing would strand their refund
+        assert!(!position_settled(&launch, &position, 0));
+        position.record_sell(1_000).unwrap();
+        assert_eq!(position.total_bonus_entitled, 0);
+        assert!(position_settled(&launch, &position, 0));
+
+        // Graduated holders keep their base tokens; only the bonus must be settled
+        launch.state = LaunchState::Graduated;
+        let position = UserPosition { total_base_tokens: 1_000, ..Default::default() };
+        assert!(position_settled(&launch, &position, 0));
+    }
+
+    #[test]
+    fn positions_from_a_closed_launch_at_the_same_address_are_stale() {
+        let mut launch = active_launch();
+        launch.created_slot = 200;
+        let mut position = UserPosition {
+            total_base_tokens: 1_000,
+            total_bonus_entitled: 500,
+            launch_slot: 200,
+            ..Default::default()
+        };
+        assert!(!position_settled(&launch, &position, 0));
+        // Left over from the launch closed at this address before: nothing it records
+        // is owed by this launch, so it can always be closed
+        position.launch_slot = 100;
+        assert!(position_settled(&launch, &position, 0));
+    }
+
+    #[test]
+    fn failed_launch_closes_once_every_token_is_refunded() {
+        let mut launch = active_launch();
+        launch.total_base_sold = 1_000;
+        assert!(!launch_settled(&launch, 0).unwrap());
+        launch.state = LaunchState::Failed;
+        assert!(!launch_settled(&launch, 0).unwrap());
+        launch.total_base_sold = 0;
+        assert!(launch_settled(&launch, 0).unwrap());
+    }
+
+    // ---- lifecycle ----
+
+    const STATES: [LaunchState; 6] = [
+        LaunchState::Pending,
+        LaunchState::Active,
+        LaunchState::Graduating,
+        LaunchState::Graduated,
+        LaunchState::Failed,
+        LaunchState::Cancelled,
+    ];
+
+    #[test]
+    fn state_transitions_follow_the_lifecycle_table() {
+        use LaunchState::*;
+        let allowed = [
+            (Pending, Active),
+            (Pending, Cancelled),
+            (Pending, Failed),
+            (Active, Graduating),
+            (Active, Graduated),
+            (Active, Failed),
+            (Graduating, Graduated),
+        ];
+        for from in STATES {
+            for to in STATES {
+                assert_eq!(from.can_transition_to(to), allowed.contains(&(from, to)), "{from:?} -> {to:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn state_predicates() {
+        use LaunchState::*;
+        for state in STATES {
+            assert_eq!(state.is_trading(), matches!(state, Pending | Active), "{state:?}");
+            assert_eq!(state.curve_closed(), matches!(state, Graduating | Graduated | Failed), "{state:?}");
+            assert_eq!(state.is_settled_success(), matches!(state, Graduating | Graduated), "{state:?}");
+        }
+    }
+
+    #[test]
+    fn set_state_starts_vesting_once_and_keeps_the_flags_in_step() {
+        let mut launch = Launch::default();
+        with_schedule(&mut launch, &default_fee_vest_schedule());
+
+        assert_eq!(launch.set_state(LaunchState::Active, 10).unwrap(), LaunchState::Pending);
+        assert!(launch.has_initial_buy && !launch.is_graduated);
+
+        launch.set_state(LaunchState::Graduating, 100).unwrap();
+        assert_eq!((launch.graduation_time, launch.milestones_unlocked), (100, 1));
+        assert!(launch.is_graduated && !launch.pool_created);
+
+        launch.set_state(LaunchState::Graduated, 200).unwrap();
+        assert_eq!(launch.graduation_time, 100);
+        assert!(launch.is_graduated && launch.pool_created);
+
+        assert_eq!(
+            launch.set_state(LaunchState::Active, 300).unwrap_err(),
+            VestigeError::InvalidStateTransition.into()
+        );
+        assert_eq!(launch.state, LaunchState::Graduated);
+    }
+
+    #[test]
+    fn failed_launch_vests_nothing_and_unlocks_no_fees() {
+        let mut launch = Launch {
+            total_creator_fees: 1_000,
+            bonus_claim_deadline: 10,
+            ..Default::default()
+        };
+        with_schedule(&mut launch, &default_fee_vest_schedule());
+        let position = UserPosition { total_bonus_entitled: 10_000, ..Default::default() };
+
+        launch.set_state(LaunchState::Active, 10).unwrap();
+        launch.set_state(LaunchState::Failed, 100).unwrap();
+        assert_eq!((launch.graduation_time, launch.milestones_unlocked), (100, 0));
+        assert!(!launch.is_graduated);
+
+        // Holders refund instead: no bonus to claim (and so none blocking the refund),
+        // no creator fees, no milestones and no claim deadline to sweep at
+        assert_eq!(vested_bonus(&launch, &position, 1_000_000).unwrap(), 0);
+        assert_eq!(creator_fees_unlocked(&launch, 1_000_000).unwrap(), 0);
+        assert_eq!(next_milestone_time(&launch), None);
+        assert!(!bonus_claim_expired(&launch, 1_000_000));
+    }
+
+    // ---- vesting and fees ----
+
+    fn with_schedule(launch: &mut Launch, steps: &[FeeVestStep]) {
+        launch.fee_vest_step_count = steps.len() as u8;
+        launch.fee_vest_schedule[..steps.len()].copy_from_slice(steps);
+    }
+
+    fn step(delay: i64, bps: u16, condition: MilestoneCondition) -> FeeVestStep {
+        FeeVestStep { delay, bps, condition }
+    }
+
+    #[test]
+    fn vested_bonus_waits_for_the_cliff_then_vests_linearly() {
+        let mut launch = Launch {
+            bonus_vest_cliff: 100,
+            bonus_vest_duration: 1_000,
+            graduation_time: 5_000,
+            ..Default::default()
+        };
+        let position = UserPosition { total_bonus_entitled: 10_000, ..Default::default() };
+        // Nothing before graduation
+        assert_eq!(vested_bonus(&launch, &position, 9_999).unwrap(), 0);
+
+        launch.state = LaunchState::Graduated;
+        assert_eq!(vested_bonus(&launch, &position, 5_099).unwrap(), 0);
+        assert_eq!(vested_bonus(&launch, &position, 5_100).unwrap(), 1_000);
+        assert_eq!(vested_bonus(&launch, &position, 5_500).unwrap(), 5_000);
+        assert_eq!(vested_bonus(&launch, &position, 9_000).unwrap(), 10_000);
+
+        launch.bonus_vest_duration = 0;
+        assert_eq!(vested_bonus(&launch, &position, 5_100).unwrap(), 10_000);
+    }
+
+    #[test]
+    fn fee_vest_schedule_bounds() {
+        use MilestoneCondition::*;
+        assert!(validate_fee_vest_schedule(&default_fee_vest_schedule()).is_ok());
+        assert!(validate_fee_vest_schedule(&[step(0, 3_000, None), step(10, 7_000, MinHolders(18))]).is_ok());
+
+        let rejected: [&[FeeVestStep]; 7] = [
+            &[],
+            &[step(0, 3_000, None), step(10, 6_999, None)],
+            &[step(0, 3_001, None), step(10, 6_999, None)],
+            &[step(10, 5_000, None), step(5, 5_000, None)],
+            &[step(0, 3_000, MinPoolPriceBps(5_000)), step(10, 7_000, None)],
+            &[step(0, 3_000, None), step(10, 7_000, MinHolders(MAX_MILESTONE_HOLDERS + 1))],
+            &[step(0, 3_000, None), step(MAX_FEE_VEST_DURATION + 1, 7_000, None)],
+        ];
+        for steps in rejected {
+            assert!(validate_fee_vest_schedule(steps).is_err(), "{steps:?}");
+        }
+    }
+
+    #[test]
+    fn creator_fees_settle_once_fully_vested_and_claimed() {
+        let mut launch = Launch {
+            total_creator_fees: 1_000,
+            state: LaunchState::Graduated,
+            ..Default::default()
+        };
+        with_schedule(&mut launch, &default_fee_vest_schedule());
+        launch.milestones_unlocked = 3;
+        launch.creator_fees_claimed = 700;
+        assert!(!creator_fees_settled(&launch, 0).unwrap());
+
+        launch.milestones_unlocked = 4;
+        assert!(!creator_fees_settled(&launch, 0).unwrap());
+        launch.creator_fees_claimed = 1_000;
+        assert!(creator_fees_settled(&launch, 0).unwrap());
+
+        // Split launches settle per recipient; rounding dust is not owed
+        launch.fee_split_count = 2;
+        launch.fee_splits[0] = FeeShare { recipient: wallet(1), bps: 3_333 };
+        launch.fee_splits[1] = FeeShare { recipient: wallet(2), bps: 6_667 };
+        launch.fee_split_claimed[0] = 333;
+        launch.fee_split_claimed[1] = 665;
+        assert!(!creator_fees_settled(&launch, 0).unwrap());
+        // 333 + 666 of 1_000: the last lamport is dust
+        launch.fee_split_claimed[1] = 666;
+        assert!(creator_fees_settled(&launch, 0).unwrap());
+    }
+
+    #[test]
+    fn graduation_bounty_takes_its_bps_up_to_the_cap() {
+        let mut launch = Launch { graduation_bounty_bps: 50, ..Default::default() };
+        assert_eq!(graduation_bounty(&launch, 10 * SOL).unwrap(), SOL / 20);
+        launch.graduation_bounty_cap = SOL / 100;
+        assert_eq!(graduation_bounty(&launch, 10 * SOL).unwrap(), SOL / 100);
+        assert_eq!(graduation_bounty(&launch, SOL).unwrap(), SOL / 200);
+    }
+
+    #[test]
+    fn graduate_to_dex_pays_the_bounty_after_a_buy_reaches_the_target() {
+        let mut launch = Launch {
+            graduation_bounty_bps: 50,
+            graduation_fee_bps: 100,
+            ..active_launch()
+        };
+        launch.total_sol_collected = launch.graduation_target - SOL / 2;
+        let quote = compute_buy(&launch, SOL, 0).unwrap();
+        launch.record_buy(&quote, true).unwrap();
+        // The buy moves the launch to Graduating without paying a bounty
+        assert!(launch.total_sol_collected >= launch.graduation_target);
+        launch.set_state(LaunchState::Graduating, 100).unwrap();
+        assert!(graduation_bounty_owed(&launch));
+
+        // graduate_to_dex pays its caller first, then lists the rest
+        let vault = launch.total_sol_collected;
+        let bounty = graduation_bounty(&launch, vault).unwrap();
+        assert_eq!(bounty, vault / 200);
+        launch.graduation_bounty_paid = bounty;
+        let (graduation_fee, bounty_withheld, quote_for_pool) =
+            pool_release_amounts(&launch, vault - bounty).unwrap();
+        assert_eq!(graduation_fee, vault / 100);
+        assert_eq!(bounty_withheld, 0);
+        assert_eq!(bounty + graduation_fee + quote_for_pool, vault);
+
+        // Never twice, and never once listed
+        assert!(!graduation_bounty_owed(&launch));
+        launch.graduation_bounty_paid = 0;
+        launch.set_state(LaunchState::Graduated, 200).unwrap();
+        assert!(!graduation_bounty_owed(&launch));
+    }
+
+    #[test]
+    fn unpaid_bounty_share_stays_out_of_the_pool() {
+        let launch = Launch {
+            graduation_bounty_bps: 50,
+            graduation_fee_bps: 100,
+            ..active_launch()
+        };
+        let (graduation_fee, bounty_withheld, quote_for_pool) =
+            pool_release_amounts(&launch, 10 * SOL).unwrap();
+        assert_eq!((graduation_fee, bounty_withheld), (SOL / 10, SOL / 20));
+        assert_eq!(quote_for_pool, 10 * SOL - SOL / 10 - SOL / 20);
+    }
+
+    #[test]
+    fn lp_reserve_after_fee_keeps_the_listing_price() {
+        assert_eq!(lp_reserve_after_fee(1_000_000, 0).unwrap(), 1_000_000);
+        assert_eq!(lp_reserve_after_fee(1_000_000, 350).unwrap(), 965_000);
+        // The vault's matching share leaves quote / tokens unchanged
+        let vault: u64 = 20 * SOL;
+        let pool_quote = vault - vault * 350 / BPS_DENOMINATOR;
+        let p_min = vault as u128 * TOKEN_PRECISION / 1_000_000;
+        assert_eq!(pool_quote as u128 * TOKEN_PRECISION / 965_000, p_min);
+    }
+
+    #[test]
+    fn snipe_fee_decays_to_zero_over_the_window() {
+        let mut launch = active_launch();
+        launch.activation_time = 1_000;
+        launch.snipe_window = 100;
+        launch.snipe_fee_bps = 1_000;
+        assert_eq!(snipe_fee(&launch, SOL, 1_000).unwrap(), SOL / 10);
+        assert_eq!(snipe_fee(&launch, SOL, 1_050).unwrap(), SOL / 20);
+        assert_eq!(snipe_fee(&launch, SOL, 1_100).unwrap(), 0);
+        launch.state = LaunchState::Pending;
+        assert_eq!(snipe_fee(&launch, SOL, 1_000).unwrap(), 0);
+    }
+
+    #[test]
+    fn quote_mint_referrals_stay_out_of_the_lamport_claim() {
+        let mut referrer = Referrer::default();
+        referrer.record_referral(1_000, SOL, false).unwrap();
+        referrer.record_referral(500, 2_000_000, true).unwrap();
+        assert_eq!(referrer.total_earned, 1_000);
+        assert_eq!(referrer.referred_volume, SOL);
+        assert_eq!(referrer.total_claimed, 0);
+        assert_eq!(referrer.total_paid_direct, 500);
+        assert_eq!(referrer.referred_volume_direct, 2_000_000);
+        assert_eq!(referrer.referred_buys, 2);
+    }
 }
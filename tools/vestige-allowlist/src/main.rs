@@ -0,0 +1,189 @@
+//! vestige-allowlist — builds a launch's presale allowlist from a CSV.
+//!
+//! Input is `wallet,max_sol` per line (a header line and `#` comments are skipped).
+//! Output is a JSON file with the Merkle root to pass as `LaunchConfig.allowlist_root`
+//! and, per wallet, the `AllowlistProof` (max allocation in lamports + proof) that
+//! wallet passes to `buy` during the presale window.
+//!
+//! Hashing is the program's own (`allowlist_leaf`, `allowlist_node`), and every
+//! proof is checked with `verify_allowlist_proof` before it is written.
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{anyhow, bail, Context, Result};
+use clap::Parser;
+use serde_json::json;
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+use vestige::{allowlist_leaf, allowlist_node, verify_allowlist_proof, MAX_ALLOWLIST_PROOF_LEN};
+
+const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+#[derive(Parser, Debug)]
+#[command(name = "vestige-allowlist", about = "Build a Vestige presale allowlist Merkle root and proofs")]
+struct Args {
+    /// CSV of `wallet,max_sol` rows.
+    #[arg(long)]
+    csv: PathBuf,
+
+    /// Where to write the root and proofs (JSON).
+    #[arg(long, default_value = "allowlist.json")]
+    out: PathBuf,
+}
+
+struct Entry {
+    wallet: Pubkey,
+    max_allocation: u64,
+}
+
+/// Parse a decimal SOL amount into lamports without going through floats.
+fn parse_sol(text: &str) -> Result<u64> {
+    let (whole, frac) = text.split_once('.').unwrap_or((text, ""));
+    if frac.len() > 9 || (whole.is_empty() && frac.is_empty()) {
+        bail!("invalid SOL amount {text:?}");
+    }
+    let whole: u64 = if whole.is_empty() { 0 } else { whole.parse()? };
+    let frac: u64 = if frac.is_empty() { 0 } else { format!("{frac:0<9}").parse()? };
+    whole
+        .checked_mul(LAMPORTS_PER_SOL)
+        .and_then(|l| l.checked_add(frac))
+        .ok_or_else(|| anyhow!("SOL amount {text:?} overflows"))
+}
+
+fn load_csv(path: &PathBuf) -> Result<Vec<Entry>> {
+    let text = fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    let mut entries = Vec::new();
+    let mut seen = HashSet::new();
+    for (n, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (wallet, max_sol) = line
+            .split_once(',')
+            .ok_or_else(|| anyhow!("line {}: expected `wallet,max_sol`", n + 1))?;
+        let Ok(wallet) = Pubkey::from_str(wallet.trim()) else {
+            if entries.is_empty() && n == 0 {
+                continue; // header
+            }
+            bail!("line {}: invalid wallet {:?}", n + 1, wallet.trim());
+        };
+        let max_allocation = parse_sol(max_sol.trim()).with_context(|| format!("line {}", n + 1))?;
+        if max_allocation == 0 {
+            bail!("line {}: allocation must be positive", n + 1);
+        }
+        if !seen.insert(wallet) {
+            bail!("line {}: duplicate wallet {wallet}", n + 1);
+        }
+        entries.push(Entry { wallet, max_allocation });
+    }
+    if entries.is_empty() {
+        bail!("{} has no allowlist entries", path.display());
+    }
+    Ok(entries)
+}
+
+/// All tree levels, leaves first. An odd node at the end of a level is carried up unchanged.
+fn build_levels(leaves: Vec<[u8; 32]>) -> Vec<Vec<[u8; 32]>> {
+    let mut levels = vec![leaves];
+    while levels.last().map_or(0, Vec::len) > 1 {
+        let next = levels
+            .last()
+            .unwrap()
+            .chunks(2)
+            .map(|pair| match pair {
+                [a, b] => allowlist_node(a, b),
+                [a] => *a,
+                _ => unreachable!(),
+            })
+            .collect();
+        levels.push(next);
+    }
+    levels
+}
+
+fn proof_for(levels: &[Vec<[u8; 32]>], mut index: usize) -> Vec<[u8; 32]> {
+    let mut proof = Vec::new();
+    for level in &levels[..levels.len() - 1] {
+        if let Some(sibling) = level.get(index ^ 1) {
+            proof.push(*sibling);
+        }
+        index /= 2;
+    }
+    proof
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::with_capacity(bytes.len() * 2), |mut out, b| {
+        let _ = write!(out, "{b:02x}");
+        out
+    })
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    let entries = load_csv(&args.csv)?;
+
+    let leaves = entries.iter().map(|e| allowlist_leaf(&e.wallet, e.max_allocation)).collect();
+    let levels = build_levels(leaves);
+    let root = levels.last().unwrap()[0];
+
+    let mut out = Vec::with_capacity(entries.len());
+    for (i, entry) in entries.iter().enumerate() {
+        let proof = proof_for(&levels, i);
+        if proof.len() > MAX_ALLOWLIST_PROOF_LEN {
+            bail!("allowlist too large: proofs exceed {MAX_ALLOWLIST_PROOF_LEN} nodes");
+        }
+        if !verify_allowlist_proof(&root, levels[0][i], &proof) {
+            bail!("internal error: proof for {} does not verify", entry.wallet);
+        }
+        out.push(json!({
+            "wallet": entry.wallet.to_string(),
+            "max_allocation": entry.max_allocation,
+            "proof": proof.iter().map(|p| hex(p)).collect::<Vec<_>>(),
+        }));
+    }
+
+    let doc = json!({
+        "root": hex(&root),
+        "root_bytes": root.to_vec(),
+        "entries": out,
+    });
+    fs::write(&args.out, serde_json::to_string_pretty(&doc)?)
+        .with_context(|| format!("writing {}", args.out.display()))?;
+
+    println!("{} wallets, root {}", entries.len(), hex(&root));
+    println!("proofs written to {}", args.out.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_sol_is_exact() {
+        assert_eq!(parse_sol("1").unwrap(), LAMPORTS_PER_SOL);
+        assert_eq!(parse_sol("0.5").unwrap(), LAMPORTS_PER_SOL / 2);
+        assert_eq!(parse_sol(".000000001").unwrap(), 1);
+        assert!(parse_sol("0.0000000001").is_err());
+        assert!(parse_sol(".").is_err());
+    }
+
+    #[test]
+    fn every_proof_verifies_against_the_root() {
+        for count in 1..=9u8 {
+            let leaves: Vec<[u8; 32]> = (0..count)
+                .map(|n| allowlist_leaf(&Pubkey::new_from_array([n; 32]), n as u64 + 1))
+                .collect();
+            let levels = build_levels(leaves.clone());
+            let root = levels.last().unwrap()[0];
+            for (i, leaf) in leaves.iter().enumerate() {
+                let proof = proof_for(&levels, i);
+                assert!(verify_allowlist_proof(&root, *leaf, &proof), "{count} leaves, leaf {i}");
+            }
+        }
+    }
+}
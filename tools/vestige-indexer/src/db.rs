@@ -0,0 +1,622 @@
+//! SQLite schema and event application.
+//!
+//! Every write is keyed by `(signature, event_index)` and derived state (positions,
+//! candles, launch totals) is only touched when that key is new, so re-processing a
+//! transaction after a crash or an overlapping checkpoint is a no-op.
+
+use crate::decode::VestigeEvent;
+use crate::source::TxRecord;
+use anyhow::Result;
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use std::path::Path;
+use vestige::LaunchState;
+
+/// Candle resolutions in seconds: 1m, 5m, 15m, 1h, 4h, 1d.
+pub const CANDLE_RESOLUTIONS: [i64; 6] = [60, 300, 900, 3_600, 14_400, 86_400];
+
+const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS checkpoint (
+    id              INTEGER PRIMARY KEY CHECK (id = 1),
+    slot            INTEGER NOT NULL,
+    signature       TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS launches (
+    launch              TEXT PRIMARY KEY,
+    creator             TEXT,
+    token_mint          TEXT,
+    name                TEXT,
+    symbol              TEXT,
+    token_supply        INTEGER,
+    bonus_pool          INTEGER,
+    lp_reserve          INTEGER,
+    start_time          INTEGER,
+    end_time            INTEGER,
+    p_max               INTEGER,
+    p_min               INTEGER,
+    r_best              INTEGER,
+    r_min               INTEGER,
+    graduation_target   INTEGER,
+    created_slot        INTEGER,
+    total_base_sold     INTEGER NOT NULL DEFAULT 0,
+    total_sol_collected INTEGER NOT NULL DEFAULT 0,
+    last_price          INTEGER,
+    trade_count         INTEGER NOT NULL DEFAULT 0,
+    is_graduated        INTEGER NOT NULL DEFAULT 0,
+    pool_created        INTEGER NOT NULL DEFAULT 0,
+    graduation_time     INTEGER,
+    milestones_unlocked INTEGER NOT NULL DEFAULT 0,
+    state               TEXT NOT NULL DEFAULT 'pending',
+    updated_slot        INTEGER
+);
+
+CREATE TABLE IF NOT EXISTS positions (
+    launch          TEXT NOT NULL,
+    user            TEXT NOT NULL,
+    base_tokens     INTEGER NOT NULL DEFAULT 0,
+    bonus_entitled  INTEGER NOT NULL DEFAULT 0,
+    bonus_claimed   INTEGER NOT NULL DEFAULT 0,
+    sol_bought      INTEGER NOT NULL DEFAULT 0,
+    sol_sold        INTEGER NOT NULL DEFAULT 0,
+    trade_count     INTEGER NOT NULL DEFAULT 0,
+    updated_slot    INTEGER,
+    PRIMARY KEY (launch, user)
+);
+
+CREATE TABLE IF NOT EXISTS trades (
+    signature       TEXT NOT NULL,
+    event_index     INTEGER NOT NULL,
+    slot            INTEGER NOT NULL,
+    block_time      INTEGER,
+    timestamp       INTEGER NOT NULL,
+    launch          TEXT NOT NULL,
+    user            TEXT NOT NULL,
+    side            TEXT NOT NULL CHECK (side IN ('buy', 'sell')),
+    sol_amount      INTEGER NOT NULL,
+    token_amount    INTEGER NOT NULL,
+    bonus           INTEGER NOT NULL,
+    protocol_fee    INTEGER NOT NULL,
+    creator_fee     INTEGER NOT NULL,
+    price           INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE INDEX IF NOT EXISTS trades_by_launch ON trades (launch, slot);
+CREATE INDEX IF NOT EXISTS trades_by_user ON trades (user, slot);
+
+CREATE TABLE IF NOT EXISTS claims (
+    signature       TEXT NOT NULL,
+    event_index     INTEGER NOT NULL,
+    slot            INTEGER NOT NULL,
+    timestamp       INTEGER NOT NULL,
+    launch          TEXT NOT NULL,
+    recipient       TEXT NOT NULL,
+    kind            TEXT NOT NULL CHECK (kind IN ('bonus', 'creator_fees')),
+    amount          INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE INDEX IF NOT EXISTS claims_by_launch ON claims (launch, slot);
+
+CREATE TABLE IF NOT EXISTS graduations (
+    signature           TEXT NOT NULL,
+    event_index         INTEGER NOT NULL,
+    slot                INTEGER NOT NULL,
+    timestamp           INTEGER NOT NULL,
+    launch              TEXT NOT NULL,
+    total_sol_collected INTEGER NOT NULL,
+    total_base_sold     INTEGER NOT NULL,
+    total_bonus_reserved INTEGER NOT NULL,
+    sol_released        INTEGER NOT NULL,
+    tokens_released     INTEGER NOT NULL,
+    pool_created        INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE TABLE IF NOT EXISTS refunds (
+    signature       TEXT NOT NULL,
+    event_index     INTEGER NOT NULL,
+    slot            INTEGER NOT NULL,
+    timestamp       INTEGER NOT NULL,
+    launch          TEXT NOT NULL,
+    user            TEXT NOT NULL,
+    token_amount    INTEGER NOT NULL,
+    refund          INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE INDEX IF NOT EXISTS refunds_by_launch ON refunds (launch, slot);
+
+CREATE TABLE IF NOT EXISTS state_changes (
+    signature       TEXT NOT NULL,
+    event_index     INTEGER NOT NULL,
+    slot            INTEGER NOT NULL,
+    timestamp       INTEGER NOT NULL,
+    launch          TEXT NOT NULL,
+    from_state      TEXT NOT NULL,
+    to_state        TEXT NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE INDEX IF NOT EXISTS state_changes_by_launch ON state_changes (launch, slot);
+
+CREATE TABLE IF NOT EXISTS candles (
+    launch          TEXT NOT NULL,
+    resolution      INTEGER NOT NULL,
+    bucket_start    INTEGER NOT NULL,
+    open            INTEGER NOT NULL,
+    high            INTEGER NOT NULL,
+    low             INTEGER NOT NULL,
+    close           INTEGER NOT NULL,
+    volume_sol      INTEGER NOT NULL,
+    volume_tokens   INTEGER NOT NULL,
+    trade_count     INTEGER NOT NULL,
+    PRIMARY KEY (launch, resolution, bucket_start)
+);
+"#;
+
+/// Lamport and token amounts are u64 on-chain; SQLite integers are i64. Real
+/// amounts stay far below i64::MAX, so a plain cast is enough.
+fn int(v: u64) -> i64 {
+    v as i64
+}
+
+/// `launches.state` / `state_changes` name of a launch state.
+fn state_name(state: LaunchState) -> &'static str {
+    match state {
+        LaunchState::Pending => "pending",
+        LaunchState::Active => "active",
+        LaunchState::Graduating => "graduating",
+        LaunchState::Graduated => "graduated",
+        LaunchState::Failed => "failed",
+        LaunchState::Cancelled => "cancelled",
+    }
+}
+
+pub struct Db {
+    conn: Connection,
+}
+
+impl Db {
+    pub fn open(path: &Path) -> Result<Self> {
+        let conn = Connection::open(path)?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        conn.pragma_update(None, "synchronous", "NORMAL")?;
+        conn.execute_batch(SCHEMA)?;
+        // Databases created before launch states were indexed lack the column
+        let has_state = conn
+            .prepare("SELECT 1 FROM pragma_table_info('launches') WHERE name = 'state'")?
+            .exists([])?;
+        if !has_state {
+            conn.execute_batch("ALTER TABLE launches ADD COLUMN state TEXT NOT NULL DEFAULT 'pending'")?;
+        }
+        Ok(Db { conn })
+    }
+
+    /// Last fully-applied `(slot, signature)`, if any.
+    pub fn checkpoint(&self) -> Result<Option<(u64, String)>> {
+        let row = self
+            .conn
+            .query_row("SELECT slot, signature FROM checkpoint WHERE id = 1", [], |row| {
+                Ok((row.get::<_, i64>(0)? as u64, row.get::<_, String>(1)?))
+            })
+            .optional()?;
+        Ok(row)
+    }
+
+    /// Apply one transaction's events and advance the checkpoint atomically.
+    /// Returns the number of events that were new.
+    pub fn apply(&mut self, tx: &TxRecord, events: &[VestigeEvent]) -> Result<usize> {
+        let db_tx = self.conn.transaction()?;
+        let mut applied = 0;
+        for (index, event) in events.iter().enumerate() {
+            if apply_event(&db_tx, tx, index as i64, event)? {
+                applied += 1;
+            }
+        }
+        db_tx.execute(
+            "INSERT INTO checkpoint (id, slot, signature) VALUES (1, ?1, ?2)
+             ON CONFLICT (id) DO UPDATE SET slot = excluded.slot, signature = excluded.signature
+             WHERE excluded.slot >= checkpoint.slot",
+            params![int(tx.slot), tx.signature],
+        )?;
+        db_tx.commit()?;
+        Ok(applied)
+    }
+}
+
+fn ensure_launch(db: &Transaction, launch: &str) -> Result<()> {
+    db.execute("INSERT OR IGNORE INTO launches (launch) VALUES (?1)", params![launch])?;
+    Ok(())
+}
+
+fn apply_event(db: &Transaction, tx: &TxRecord, index: i64, event: &VestigeEvent) -> Result<bool> {
+    let slot = int(tx.slot);
+    match event {
+        VestigeEvent::LaunchCreated(e) => {
+            let launch = e.launch.to_string();
+            ensure_launch(db, &launch)?;
+            let changed = db.execute(
+                "UPDATE launches SET creator = ?2, token_mint = ?3, name = ?4, symbol = ?5,
+                    token_supply = ?6, bonus_pool = ?7, lp_reserve = ?8, start_time = ?9,
+                    end_time = ?10, p_max = ?11, p_min = ?12, r_best = ?13, r_min = ?14,
+                    graduation_target = ?15, created_slot = ?16, last_price = COALESCE(last_price, ?11)
+                 WHERE launch = ?1 AND created_slot IS NULL",
+                params![
+                    launch,
+                    e.creator.to_string(),
+                    e.token_mint.to_string(),
+                    e.name,
+                    e.symbol,
+                    int(e.token_supply),
+                    int(e.bonus_pool),
+                    int(e.lp_reserve),
+                    e.start_time,
+                    e.end_time,
+                    int(e.p_max),
+                    int(e.p_min),
+                    int(e.r_best),
+                    int(e.r_min),
+                    int(e.graduation_target),
+                    slot,
+                ],
+            )?;
+            Ok(changed > 0)
+        }
+        VestigeEvent::Buy(e) => {
+            let trade = Trade {
+                launch: e.launch.to_string(),
+                user: e.user.to_string(),
+                side: "buy",
+                sol_amount: e.sol_amount,
+                token_amount: e.base_tokens,
+                bonus: e.bonus,
+                protocol_fee: e.protocol_fee,
+                creator_fee: e.creator_fee,
+                price: e.curve_price,
+                timestamp: e.timestamp,
+                position_base_tokens: e.position_base_tokens,
+                position_bonus_entitled: e.position_bonus_entitled,
+                total_base_sold: e.total_base_sold,
+                total_sol_collected: e.total_sol_collected,
+            };
+            apply_trade(db, tx, index, &trade)
+        }
+        VestigeEvent::Sell(e) => {
+            let trade = Trade {
+                launch: e.launch.to_string(),
+                user: e.user.to_string(),
+                side: "sell",
+                sol_amount: e.sol_net,
+                token_amount: e.token_amount,
+                bonus: e.bonus_forfeited,
+                protocol_fee: e.protocol_fee,
+                creator_fee: e.creator_fee,
+                price: e.curve_price,
+                timestamp: e.timestamp,
+                position_base_tokens: e.position_base_tokens,
+                position_bonus_entitled: e.position_bonus_entitled,
+                total_base_sold: e.total_base_sold,
+                total_sol_collected: e.total_sol_collected,
+            };
+            apply_trade(db, tx, index, &trade)
+        }
+        VestigeEvent::Graduation(e) => {
+            let launch = e.launch.to_string();
+            let inserted = db.execute(
+                "INSERT OR IGNORE INTO graduations (signature, event_index, slot, timestamp, launch,
+                    total_sol_collected, total_base_sold, total_bonus_reserved, sol_released,
+                    tokens_released, pool_created)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                params![
+                    tx.signature,
+                    index,
+                    slot,
+                    e.timestamp,
+                    launch,
+                    int(e.total_sol_collected),
+                    int(e.total_base_sold),
+                    int(e.total_bonus_reserved),
+                    int(e.sol_released),
+                    int(e.tokens_released),
+                    e.pool_created,
+                ],
+            )?;
+            if inserted == 0 {
+                return Ok(false);
+            }
+            ensure_launch(db, &launch)?;
+            db.execute(
+                "UPDATE launches SET is_graduated = 1, pool_created = MAX(pool_created, ?2),
+                    graduation_time = COALESCE(graduation_time, ?3),
+                    milestones_unlocked = MAX(milestones_unlocked, ?5), updated_slot = ?4
+                 WHERE launch = ?1",
+                params![launch, e.pool_created, e.timestamp, slot, e.milestones_unlocked],
+            )?;
+            Ok(true)
+        }
+        VestigeEvent::BonusClaimed(e) => {
+            let launch = e.launch.to_string();
+            let user = e.user.to_string();
+            if !insert_claim(db, tx, index, e.timestamp, &launch, &user, "bonus", e.amount)? {
+                return Ok(false);
+            }
+            db.execute(
+                "INSERT INTO positions (launch, user, bonus_claimed, updated_slot) VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT (launch, user) DO UPDATE SET
+                    bonus_claimed = bonus_claimed + excluded.bonus_claimed,
+                    updated_slot = excluded.updated_slot",
+                params![launch, user, int(e.amount), slot],
+            )?;
+            Ok(true)
+        }
+        VestigeEvent::CreatorFeesClaimed(e) => insert_claim(
+            db,
+            tx,
+            index,
+            e.timestamp,
+            &e.launch.to_string(),
+            &e.recipient.to_string(),
+            "creator_fees",
+            e.amount,
+        ),
+        VestigeEvent::MilestoneAdvanced(e) => {
+            let launch = e.launch.to_string();
+            ensure_launch(db, &launch)?;
+            let changed = db.execute(
+                "UPDATE launches SET milestones_unlocked = MAX(milestones_unlocked, ?2), updated_slot = ?3
+                 WHERE launch = ?1",
+                params![launch, e.milestones_unlocked, slot],
+            )?;
+            Ok(changed > 0)
+        }
+        VestigeEvent::LaunchStateChanged(e) => {
+            let launch = e.launch.to_string();
+            let inserted = db.execute(
+                "INSERT OR IGNORE INTO state_changes (signature, event_index, slot, timestamp, launch,
+                    from_state, to_state)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    tx.signature,
+                    index,
+                    slot,
+                    e.timestamp,
+                    launch,
+                    state_name(e.from),
+                    state_name(e.to),
+                ],
+            )?;
+            if inserted == 0 {
+                return Ok(false);
+            }
+            ensure_launch(db, &launch)?;
+            db.execute(
+                "UPDATE launches SET state = ?2, updated_slot = ?3 WHERE launch = ?1",
+                params![launch, state_name(e.to), slot],
+            )?;
+            Ok(true)
+        }
+        VestigeEvent::LaunchCancelled(e) => {
+            // Cancelled is terminal, so re-applying it is harmless
+            let launch = e.launch.to_string();
+            ensure_launch(db, &launch)?;
+            let changed = db.execute(
+                "UPDATE launches SET state = ?2, updated_slot = ?3 WHERE launch = ?1 AND state != ?2",
+                params![launch, state_name(LaunchState::Cancelled), slot],
+            )?;
+            Ok(changed > 0)
+        }
+        VestigeEvent::Refund(e) => {
+            let launch = e.launch.to_string();
+            let user = e.user.to_string();
+            let inserted = db.execute(
+                "INSERT OR IGNORE INTO refunds (signature, event_index, slot, timestamp, launch, user,
+                    token_amount, refund)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    tx.signature,
+                    index,
+                    slot,
+                    e.timestamp,
+                    launch,
+                    user,
+                    int(e.token_amount),
+                    int(e.refund),
+                ],
+            )?;
+            if inserted == 0 {
+                return Ok(false);
+            }
+            ensure_launch(db, &launch)?;
+            db.execute(
+                "UPDATE launches SET total_base_sold = ?2, total_sol_collected = ?3, updated_slot = ?4
+                 WHERE launch = ?1",
+                params![launch, int(e.total_base_sold), int(e.total_sol_collected), slot],
+            )?;
+            db.execute(
+                "INSERT INTO positions (launch, user, base_tokens, bonus_entitled, updated_slot)
+                 VALUES (?1, ?2, ?3, ?4, ?5)
+                 ON CONFLICT (launch, user) DO UPDATE SET
+                    base_tokens = excluded.base_tokens,
+                    bonus_entitled = excluded.bonus_entitled,
+                    updated_slot = excluded.updated_slot",
+                params![
+                    launch,
+                    user,
+                    int(e.position_base_tokens),
+                    int(e.position_bonus_entitled),
+                    slot,
+                ],
+            )?;
+            Ok(true)
+        }
+    }
+}
+
+struct Trade {
+    launch: String,
+    user: String,
+    side: &'static str,
+    sol_amount: u64,
+    token_amount: u64,
+    bonus: u64,
+    protocol_fee: u64,
+    creator_fee: u64,
+    price: u64,
+    timestamp: i64,
+    position_base_tokens: u64,
+    position_bonus_entitled: u64,
+    total_base_sold: u64,
+    total_sol_collected: u64,
+}
+
+fn apply_trade(db: &Transaction, tx: &TxRecord, index: i64, t: &Trade) -> Result<bool> {
+    let slot = int(tx.slot);
+    let inserted = db.execute(
+        "INSERT OR IGNORE INTO trades (signature, event_index, slot, block_time, timestamp, launch, user,
+            side, sol_amount, token_amount, bonus, protocol_fee, creator_fee, price)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+        params![
+            tx.signature,
+            index,
+            slot,
+            tx.block_time,
+            t.timestamp,
+            t.launch,
+            t.user,
+            t.side,
+            int(t.sol_amount),
+            int(t.token_amount),
+            int(t.bonus),
+            int(t.protocol_fee),
+            int(t.creator_fee),
+            int(t.price),
+        ],
+    )?;
+    if inserted == 0 {
+        return Ok(false);
+    }
+
+    ensure_launch(db, &t.launch)?;
+    db.execute(
+        "UPDATE launches SET total_base_sold = ?2, total_sol_collected = ?3, last_price = ?4,
+            trade_count = trade_count + 1, updated_slot = ?5
+         WHERE launch = ?1",
+        params![t.launch, int(t.total_base_sold), int(t.total_sol_collected), int(t.price), slot],
+    )?;
+
+    let (bought, sold) = if t.side == "buy" { (t.sol_amount, 0) } else { (0, t.sol_amount) };
+    db.execute(
+        "INSERT INTO positions (launch, user, base_tokens, bonus_entitled, sol_bought, sol_sold,
+            trade_count, updated_slot)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, 1, ?7)
+         ON CONFLICT (launch, user) DO UPDATE SET
+            base_tokens = excluded.base_tokens,
+            bonus_entitled = excluded.bonus_entitled,
+            sol_bought = sol_bought + excluded.sol_bought,
+            sol_sold = sol_sold + excluded.sol_sold,
+            trade_count = trade_count + 1,
+            updated_slot = excluded.updated_slot",
+        params![
+            t.launch,
+            t.user,
+            int(t.position_base_tokens),
+            int(t.position_bonus_entitled),
+            int(bought),
+            int(sold),
+            slot,
+        ],
+    )?;
+
+    for resolution in CANDLE_RESOLUTIONS {
+        let bucket = t.timestamp - t.timestamp.rem_euclid(resolution);
+        db.execute(
+            "INSERT INTO candles (launch, resolution, bucket_start, open, high, low, close,
+                volume_sol, volume_tokens, trade_count)
+             VALUES (?1, ?2, ?3, ?4, ?4, ?4, ?4, ?5, ?6, 1)
+             ON CONFLICT (launch, resolution, bucket_start) DO UPDATE SET
+                high = MAX(high, excluded.high),
+                low = MIN(low, excluded.low),
+                close = excluded.close,
+                volume_sol = volume_sol + excluded.volume_sol,
+                volume_tokens = volume_tokens + excluded.volume_tokens,
+                trade_count = trade_count + 1",
+            params![
+                t.launch,
+                resolution,
+                bucket,
+                int(t.price),
+                int(t.sol_amount),
+                int(t.token_amount),
+            ],
+        )?;
+    }
+
+    Ok(true)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn insert_claim(
+    db: &Transaction,
+    tx: &TxRecord,
+    index: i64,
+    timestamp: i64,
+    launch: &str,
+    recipient: &str,
+    kind: &str,
+    amount: u64,
+) -> Result<bool> {
+    let inserted = db.execute(
+        "INSERT OR IGNORE INTO claims (signature, event_index, slot, timestamp, launch, recipient, kind, amount)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![tx.signature, index, int(tx.slot), timestamp, launch, recipient, kind, int(amount)],
+    )?;
+    Ok(inserted > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::prelude::Pubkey;
+    use vestige::{LaunchCancelled, LaunchStateChanged};
+
+    fn tx(signature: &str, slot: u64) -> TxRecord {
+        TxRecord { signature: signature.to_string(), slot, block_time: None, logs: Vec::new() }
+    }
+
+    fn state_changed(launch: Pubkey, from: LaunchState, to: LaunchState) -> VestigeEvent {
+        VestigeEvent::LaunchStateChanged(LaunchStateChanged { launch, from, to, timestamp: 0 })
+    }
+
+    fn state(db: &Db, launch: &Pubkey) -> String {
+        db.conn
+            .query_row("SELECT state FROM launches WHERE launch = ?1", [launch.to_string()], |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn state_changes_are_applied_once() {
+        let mut db = Db::open(Path::new(":memory:")).unwrap();
+        let launch = Pubkey::new_unique();
+        let activate = [state_changed(launch, LaunchState::Pending, LaunchState::Active)];
+        assert_eq!(db.apply(&tx("a", 1), &activate).unwrap(), 1);
+        assert_eq!(state(&db, &launch), "active");
+
+        let fail = [state_changed(launch, LaunchState::Active, LaunchState::Failed)];
+        assert_eq!(db.apply(&tx("b", 2), &fail).unwrap(), 1);
+        // Re-processing an older transaction changes nothing
+        assert_eq!(db.apply(&tx("a", 1), &activate).unwrap(), 0);
+        assert_eq!(state(&db, &launch), "failed");
+    }
+
+    #[test]
+    fn launch_cancelled_marks_the_launch_cancelled() {
+        let mut db = Db::open(Path::new(":memory:")).unwrap();
+        let launch = Pubkey::new_unique();
+        let cancelled = VestigeEvent::LaunchCancelled(LaunchCancelled {
+            launch,
+            creator: Pubkey::new_unique(),
+            tokens_returned: 1,
+            timestamp: 0,
+        });
+        let events = [state_changed(launch, LaunchState::Pending, LaunchState::Cancelled), cancelled];
+        assert_eq!(db.apply(&tx("c", 3), &events).unwrap(), 1);
+        assert_eq!(state(&db, &launch), "cancelled");
+    }
+}
@@ -0,0 +1,154 @@
+//! Decoding of Vestige `emit!` events out of transaction log messages.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use base64::Engine;
+use vestige::{
+    BonusClaimed, BuyEvent, CreatorFeesClaimed, GraduationEvent, LaunchCancelled, LaunchCreated,
+    LaunchStateChanged, MilestoneAdvanced, RefundEvent, SellEvent,
+};
+
+const PROGRAM_DATA: &str = "Program data: ";
+
+/// Every event the indexer understands. Unknown discriminators are skipped so an
+/// older indexer keeps working against a newer program.
+pub enum VestigeEvent {
+    // Boxed: the launch config makes it several times larger than any other event
+    LaunchCreated(Box<LaunchCreated>),
+    Buy(BuyEvent),
+    Sell(SellEvent),
+    Graduation(GraduationEvent),
+    BonusClaimed(BonusClaimed),
+    CreatorFeesClaimed(CreatorFeesClaimed),
+    MilestoneAdvanced(MilestoneAdvanced),
+    LaunchStateChanged(LaunchStateChanged),
+    LaunchCancelled(LaunchCancelled),
+    Refund(RefundEvent),
+}
+
+/// Extract the events emitted by `program_id` (not by programs it CPIs into,
+/// nor by other top-level instructions) from a transaction's log messages.
+pub fn events_from_logs(program_id: &Pubkey, logs: &[String]) -> Vec<VestigeEvent> {
+    let program = program_id.to_string();
+    let mut stack: Vec<&str> = Vec::new();
+    let mut events = Vec::new();
+
+    for line in logs {
+        if let Some(rest) = line.strip_prefix("Program ") {
+            let mut parts = rest.split_whitespace();
+            let id = parts.next().unwrap_or_default();
+            match parts.next() {
+                Some("invoke") => {
+                    stack.push(id);
+                    continue;
+                }
+                Some("success") | Some("failed:") => {
+                    stack.pop();
+                    continue;
+                }
+                _ => {}
+            }
+        }
+
+        let Some(data) = line.strip_prefix(PROGRAM_DATA) else {
+            continue;
+        };
+        if stack.last() != Some(&program.as_str()) {
+            continue;
+        }
+        let Ok(bytes) = base64::engine::general_purpose::STANDARD.decode(data.trim()) else {
+            continue;
+        };
+        if let Some(event) = decode_event(&bytes) {
+            events.push(event);
+        }
+    }
+
+    events
+}
+
+fn decode_event(bytes: &[u8]) -> Option<VestigeEvent> {
+    if bytes.len() < 8 {
+        return None;
+    }
+    let (disc, mut data) = bytes.split_at(8);
+
+    macro_rules! try_decode {
+        ($ty:ty, $variant:ident) => {
+            if disc == <$ty as Discriminator>::DISCRIMINATOR {
+                return <$ty>::deserialize(&mut data).ok().map(VestigeEvent::$variant);
+            }
+        };
+    }
+
+    if disc == LaunchCreated::DISCRIMINATOR {
+        return LaunchCreated::deserialize(&mut data)
+            .ok()
+            .map(|e| VestigeEvent::LaunchCreated(Box::new(e)));
+    }
+    try_decode!(BuyEvent, Buy);
+    try_decode!(SellEvent, Sell);
+    try_decode!(GraduationEvent, Graduation);
+    try_decode!(BonusClaimed, BonusClaimed);
+    try_decode!(CreatorFeesClaimed, CreatorFeesClaimed);
+    try_decode!(MilestoneAdvanced, MilestoneAdvanced);
+    try_decode!(LaunchStateChanged, LaunchStateChanged);
+    try_decode!(LaunchCancelled, LaunchCancelled);
+    try_decode!(RefundEvent, Refund);
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::Event;
+    use vestige::LaunchState;
+
+    fn program_data(event: &impl Event) -> String {
+        format!("{PROGRAM_DATA}{}", base64::engine::general_purpose::STANDARD.encode(event.data()))
+    }
+
+    fn state_changed(to: LaunchState) -> LaunchStateChanged {
+        LaunchStateChanged { launch: Pubkey::new_unique(), from: LaunchState::Active, to, timestamp: 42 }
+    }
+
+    #[test]
+    fn decodes_only_events_of_the_program_itself() {
+        let program = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let failed = state_changed(LaunchState::Failed);
+        let logs = vec![
+            format!("Program {program} invoke [1]"),
+            format!("Program {other} invoke [2]"),
+            program_data(&state_changed(LaunchState::Graduating)),
+            format!("Program {other} success"),
+            program_data(&failed),
+            "Program data: not base64!".to_string(),
+            format!("Program {program} success"),
+            program_data(&state_changed(LaunchState::Graduated)),
+        ];
+        let events = events_from_logs(&program, &logs);
+        assert_eq!(events.len(), 1);
+        let VestigeEvent::LaunchStateChanged(e) = &events[0] else {
+            panic!("expected LaunchStateChanged");
+        };
+        assert_eq!(e.launch, failed.launch);
+        assert_eq!((e.from, e.to, e.timestamp), (LaunchState::Active, LaunchState::Failed, 42));
+    }
+
+    #[test]
+    fn decodes_launch_cancelled() {
+        let cancelled = LaunchCancelled {
+            launch: Pubkey::new_unique(),
+            creator: Pubkey::new_unique(),
+            tokens_returned: 1_000,
+            timestamp: 7,
+        };
+        let bytes = cancelled.data();
+        let Some(VestigeEvent::LaunchCancelled(e)) = decode_event(&bytes) else {
+            panic!("expected LaunchCancelled");
+        };
+        assert_eq!((e.launch, e.tokens_returned), (cancelled.launch, 1_000));
+        assert!(decode_event(&bytes[..7]).is_none());
+    }
+}
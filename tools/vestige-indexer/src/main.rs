@@ -0,0 +1,117 @@
+//! vestige-indexer — builds a SQLite database of launches, positions, trades,
+//! claims and OHLC candles from the Vestige program's `emit!` events.
+//!
+//! Sources:
+//!   --rpc <url>    poll a (local) validator via `getSignaturesForAddress` + `getTransaction`
+//!   --dump <file>  JSON array / JSON lines of `getTransaction` results
+//!
+//! Progress is checkpointed per transaction, so the indexer can be stopped and
+//! restarted at any point and resumes from the last applied slot.
+
+mod db;
+mod decode;
+mod source;
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{bail, Result};
+use clap::Parser;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::thread;
+use std::time::Duration;
+
+use db::Db;
+use decode::events_from_logs;
+use source::{load_dump, RpcSource, TxRecord};
+
+#[derive(Parser, Debug)]
+#[command(name = "vestige-indexer", about = "Index Vestige program events into SQLite")]
+struct Args {
+    /// SQLite database path (created if missing).
+    #[arg(long, default_value = "vestige-index.db")]
+    db: PathBuf,
+
+    /// JSON-RPC endpoint to poll, e.g. http://127.0.0.1:8899.
+    #[arg(long, conflicts_with = "dump")]
+    rpc: Option<String>,
+
+    /// Transaction dump to ingest instead of polling an RPC.
+    #[arg(long)]
+    dump: Option<PathBuf>,
+
+    /// Program id to index (defaults to the id the crate was built with).
+    #[arg(long)]
+    program_id: Option<String>,
+
+    /// Keep polling the RPC for new transactions instead of exiting after catch-up.
+    #[arg(long)]
+    follow: bool,
+
+    /// Seconds between polls in --follow mode.
+    #[arg(long, default_value_t = 5)]
+    poll_secs: u64,
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    let program_id = match &args.program_id {
+        Some(id) => Pubkey::from_str(id)?,
+        None => vestige::ID,
+    };
+
+    let mut db = Db::open(&args.db)?;
+    if let Some((slot, sig)) = db.checkpoint()? {
+        eprintln!("resuming from slot {slot} ({sig})");
+    }
+
+    match (&args.rpc, &args.dump) {
+        (Some(url), None) => {
+            let rpc = RpcSource::new(url);
+            loop {
+                let applied = sync_rpc(&mut db, &rpc, &program_id)?;
+                if applied > 0 {
+                    eprintln!("applied {applied} events");
+                }
+                if !args.follow {
+                    break;
+                }
+                thread::sleep(Duration::from_secs(args.poll_secs));
+            }
+        }
+        (None, Some(path)) => {
+            let min_slot = db.checkpoint()?.map(|(slot, _)| slot).unwrap_or(0);
+            let records = load_dump(path)?;
+            let mut applied = 0;
+            for record in records.iter().filter(|r| r.slot >= min_slot) {
+                applied += ingest(&mut db, record, &program_id)?;
+            }
+            eprintln!("applied {applied} events from {} transactions", records.len());
+        }
+        _ => bail!("exactly one of --rpc or --dump is required"),
+    }
+
+    Ok(())
+}
+
+/// Fetch and apply everything newer than the checkpoint. Returns events applied.
+fn sync_rpc(db: &mut Db, rpc: &RpcSource, program_id: &Pubkey) -> Result<usize> {
+    let checkpoint = db.checkpoint()?;
+    let (min_slot, until) = match &checkpoint {
+        Some((slot, sig)) => (*slot, Some(sig.as_str())),
+        None => (0, None),
+    };
+
+    let mut applied = 0;
+    for (signature, _slot) in rpc.new_signatures(program_id, until, min_slot)? {
+        match rpc.transaction(&signature)? {
+            Some(record) => applied += ingest(db, &record, program_id)?,
+            None => eprintln!("skipping {signature}: not available or failed"),
+        }
+    }
+    Ok(applied)
+}
+
+fn ingest(db: &mut Db, record: &TxRecord, program_id: &Pubkey) -> Result<usize> {
+    let events = events_from_logs(program_id, &record.logs);
+    db.apply(record, &events)
+}
@@ -0,0 +1,177 @@
+//! Transaction sources: a JSON-RPC endpoint (local validator) or a JSON dump.
+//!
+//! Both sources yield the same `getTransaction` (`"json"` encoding) shape, so a dump
+//! is simply a JSON array — or one object per line — of `getTransaction` results.
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{anyhow, bail, Context, Result};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::Path;
+
+/// Page size for `getSignaturesForAddress` (RPC maximum).
+const SIGNATURE_PAGE: usize = 1_000;
+
+/// One confirmed, successful transaction touching the program.
+#[derive(Debug, Clone)]
+pub struct TxRecord {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub logs: Vec<String>,
+}
+
+impl TxRecord {
+    /// Parse a `getTransaction` result. Returns `None` for failed transactions.
+    pub fn from_json(value: &Value) -> Result<Option<Self>> {
+        let meta = value.get("meta").ok_or_else(|| anyhow!("transaction without meta"))?;
+        if !meta.get("err").map(Value::is_null).unwrap_or(true) {
+            return Ok(None);
+        }
+        let signature = value
+            .pointer("/transaction/signatures/0")
+            .and_then(Value::as_str)
+            .ok_or_else(|| anyhow!("transaction without signature"))?
+            .to_string();
+        let slot = value
+            .get("slot")
+            .and_then(Value::as_u64)
+            .ok_or_else(|| anyhow!("transaction {signature} without slot"))?;
+        let logs = meta
+            .get("logMessages")
+            .and_then(Value::as_array)
+            .map(|lines| {
+                lines
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(Some(TxRecord {
+            signature,
+            slot,
+            block_time: value.get("blockTime").and_then(Value::as_i64),
+            logs,
+        }))
+    }
+}
+
+/// Load every successful transaction from a dump file, oldest first.
+pub fn load_dump(path: &Path) -> Result<Vec<TxRecord>> {
+    let raw = fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    let values: Vec<Value> = if raw.trim_start().starts_with('[') {
+        serde_json::from_str(&raw).context("parsing dump as a JSON array")?
+    } else {
+        raw.lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect::<std::result::Result<_, _>>()
+            .context("parsing dump as JSON lines")?
+    };
+
+    let mut records = Vec::with_capacity(values.len());
+    for value in &values {
+        if let Some(record) = TxRecord::from_json(value)? {
+            records.push(record);
+        }
+    }
+    // Stable sort keeps file order within a slot.
+    records.sort_by_key(|r| r.slot);
+    Ok(records)
+}
+
+/// Minimal blocking JSON-RPC client — only the two calls the indexer needs.
+pub struct RpcSource {
+    url: String,
+    agent: ureq::Agent,
+}
+
+impl RpcSource {
+    pub fn new(url: &str) -> Self {
+        RpcSource {
+            url: url.to_string(),
+            agent: ureq::AgentBuilder::new().build(),
+        }
+    }
+
+    fn call(&self, method: &str, params: Value) -> Result<Value> {
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let response: Value = self
+            .agent
+            .post(&self.url)
+            .send_json(body)
+            .with_context(|| format!("{method} request"))?
+            .into_json()
+            .with_context(|| format!("{method} response"))?;
+        if let Some(err) = response.get("error") {
+            bail!("{method} failed: {err}");
+        }
+        Ok(response.get("result").cloned().unwrap_or(Value::Null))
+    }
+
+    /// Signatures for `program_id` newer than the checkpoint, oldest first.
+    /// Walks backwards page by page until `until` (or a slot below `min_slot`) is reached.
+    pub fn new_signatures(
+        &self,
+        program_id: &Pubkey,
+        until: Option<&str>,
+        min_slot: u64,
+    ) -> Result<Vec<(String, u64)>> {
+        let mut out = Vec::new();
+        let mut before: Option<String> = None;
+
+        loop {
+            let mut config = json!({ "limit": SIGNATURE_PAGE, "commitment": "confirmed" });
+            if let Some(before) = &before {
+                config["before"] = json!(before);
+            }
+            if let Some(until) = until {
+                config["until"] = json!(until);
+            }
+            let page = self.call("getSignaturesForAddress", json!([program_id.to_string(), config]))?;
+            let page = page.as_array().cloned().unwrap_or_default();
+            let page_len = page.len();
+
+            let mut reached_checkpoint = false;
+            for entry in &page {
+                let (Some(sig), Some(slot)) = (
+                    entry.get("signature").and_then(Value::as_str),
+                    entry.get("slot").and_then(Value::as_u64),
+                ) else {
+                    continue;
+                };
+                if slot < min_slot {
+                    reached_checkpoint = true;
+                    break;
+                }
+                before = Some(sig.to_string());
+                if entry.get("err").map(Value::is_null).unwrap_or(true) {
+                    out.push((sig.to_string(), slot));
+                }
+            }
+
+            if reached_checkpoint || page_len < SIGNATURE_PAGE {
+                break;
+            }
+        }
+
+        out.reverse();
+        Ok(out)
+    }
+
+    pub fn transaction(&self, signature: &str) -> Result<Option<TxRecord>> {
+        let result = self.call(
+            "getTransaction",
+            json!([
+                signature,
+                { "encoding": "json", "commitment": "confirmed", "maxSupportedTransactionVersion": 0 }
+            ]),
+        )?;
+        if result.is_null() {
+            return Ok(None);
+        }
+        TxRecord::from_json(&result)
+    }
+}
@@ -0,0 +1,537 @@
+//! vestige-keeper — cranks the program's permissionless instructions.
+//!
+//! Every poll it loads all `Launch` accounts and:
+//!   * calls `graduate` once `graduation_ready` holds: at the target it moves the
+//!     launch to Graduating, past its end time short of the target to Failed
+//!   * lists Graduating launches: `graduate_to_dex` followed, in the same transaction,
+//!     by the Raydium CPMM `initialize` that seeds the pool with the released liquidity
+//!     (the keeper pays the pool creation fee and earns any graduation bounty)
+//!   * calls `advance_milestone` once the next milestone's time lock has expired,
+//!     supplying the pool vaults or holder accounts its performance condition needs
+//!
+//! Conditions are re-derived from fresh account state each poll and the program
+//! rejects stale cranks (`AlreadyGraduated`, `PoolAlreadyCreated`,
+//! `MilestoneNotYetUnlocked`, ...), so running several keepers or restarting one
+//! mid-flight is safe. Logs are JSON lines.
+
+use anchor_client::anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use anchor_client::solana_sdk::commitment_config::CommitmentConfig;
+use anchor_client::solana_sdk::instruction::{AccountMeta, Instruction};
+use anchor_client::solana_sdk::pubkey::Pubkey;
+use anchor_client::solana_sdk::signature::{read_keypair_file, Keypair, Signature};
+use anchor_client::solana_sdk::signer::Signer;
+use anchor_client::solana_sdk::{pubkey, sysvar};
+use anchor_client::{Client, ClientError, Cluster, Program};
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::associated_token::spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+use anchor_spl::token::spl_token;
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::str::FromStr;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use solana_compute_budget_interface::ComputeBudgetInstruction;
+use vestige::{
+    graduation_bounty, graduation_bounty_owed, graduation_ready, next_milestone_time,
+    pool_release_amounts, pool_token_amounts, raydium_pool_vaults, raydium_vault_authority,
+    Launch, LaunchState, MilestoneCondition, VestigeError, PROTOCOL_TREASURY,
+    RAYDIUM_AMM_CONFIG_INDEX, RAYDIUM_CPMM_PROGRAM_ID, VAULT_SEED,
+};
+
+/// Raydium CPMM pool creation fee receiver (same address on devnet and mainnet).
+const RAYDIUM_CREATE_POOL_FEE: Pubkey = pubkey!("3oE58BKVt8KuYkGxx8zBojugnymWmBiyafWgMrnb6eYy");
+
+/// Anchor discriminator of Raydium CPMM `initialize`: sha256("global:initialize")[..8].
+const RAYDIUM_INITIALIZE_DISCRIMINATOR: [u8; 8] = [175, 175, 109, 31, 13, 152, 155, 237];
+
+/// Compute budget for `graduate_to_dex` plus the Raydium pool creation.
+const LISTING_COMPUTE_UNITS: u32 = 600_000;
+
+#[derive(Parser, Debug)]
+#[command(name = "vestige-keeper", about = "Graduate and list launches, and advance creator-fee milestones")]
+struct Args {
+    /// Cluster: localnet, devnet, mainnet or an RPC URL.
+    #[arg(long, default_value = "localnet")]
+    cluster: String,
+
+    /// Fee-payer keypair for crank transactions.
+    #[arg(long, default_value = "~/.config/solana/id.json")]
+    keypair: String,
+
+    /// Seconds between polls.
+    #[arg(long, default_value_t = 10)]
+    poll_secs: u64,
+
+    /// Attempts per crank before giving up until the next poll.
+    #[arg(long, default_value_t = 3)]
+    max_retries: u32,
+
+    /// Seconds to wait before re-submitting a crank that was already sent.
+    #[arg(long, default_value_t = 60)]
+    resubmit_secs: u64,
+
+    /// Run a single poll and exit.
+    #[arg(long)]
+    once: bool,
+}
+
+/// A crank the keeper can submit. Milestone cranks carry the level they advance
+/// from, so a crank is never confused with the one for the next level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Crank {
+    Graduate,
+    GraduateToDex,
+    AdvanceMilestone { from: u8 },
+}
+
+impl Crank {
+    fn name(&self) -> &'static str {
+        match self {
+            Crank::Graduate => "graduate",
+            Crank::GraduateToDex => "graduate_to_dex",
+            Crank::AdvanceMilestone { .. } => "advance_milestone",
+        }
+    }
+}
+
+/// The crank a launch is due for at `now`, if any. Closing the curve comes first, then
+/// listing; milestones wait until the launch no longer needs either.
+fn due_crank(launch: &Launch, now: i64) -> Option<Crank> {
+    if graduation_ready(launch, now) {
+        return Some(Crank::Graduate);
+    }
+    if launch.state == LaunchState::Graduating {
+        return Some(Crank::GraduateToDex);
+    }
+    next_milestone_time(launch)
+        .filter(|t| now >= *t)
+        .map(|_| Crank::AdvanceMilestone { from: launch.milestones_unlocked })
+}
+
+/// Quote and tokens `graduate_to_dex` releases for the pool when the keeper calls it,
+/// given the vault's releasable quote beforehand: the keeper is paid any bounty still
+/// owed first, then the program keeps the graduation fee and unpaid bounty share out.
+fn pool_amounts(launch: &Launch, vault_balance: u64) -> anchor_client::anchor_lang::Result<(u64, u64)> {
+    let mut launch = launch.clone();
+    let mut balance = vault_balance;
+    if graduation_bounty_owed(&launch) {
+        launch.graduation_bounty_paid = graduation_bounty(&launch, balance)?;
+        balance -= launch.graduation_bounty_paid;
+    }
+    let (_, _, quote_for_pool) = pool_release_amounts(&launch, balance)?;
+    let (tokens_for_pool, _) = pool_token_amounts(&launch)?;
+    Ok((quote_for_pool, tokens_for_pool))
+}
+
+/// Raydium CPMM `initialize` for the canonical `token_mint`/`quote_mint` pool, seeded
+/// from the payer's token accounts and opening immediately. Account order follows the
+/// Raydium CPMM IDL.
+fn raydium_initialize(
+    payer: Pubkey,
+    token_mint: Pubkey,
+    quote_mint: Pubkey,
+    payer_token: Pubkey,
+    payer_quote: Pubkey,
+    tokens: u64,
+    quote: u64,
+) -> Instruction {
+    let program = &RAYDIUM_CPMM_PROGRAM_ID;
+    let (amm_config, _) =
+        Pubkey::find_program_address(&[b"amm_config", &RAYDIUM_AMM_CONFIG_INDEX.to_le_bytes()], program);
+    // token_0 is the mint with the smaller key
+    let quote_first = quote_mint < token_mint;
+    let (mint_0, mint_1, account_0, account_1, amount_0, amount_1) = if quote_first {
+        (quote_mint, token_mint, payer_quote, payer_token, quote, tokens)
+    } else {
+        (token_mint, quote_mint, payer_token, payer_quote, tokens, quote)
+    };
+    let (pool_state, _) =
+        Pubkey::find_program_address(&[b"pool", amm_config.as_ref(), mint_0.as_ref(), mint_1.as_ref()], program);
+    let (lp_mint, _) = Pubkey::find_program_address(&[b"pool_lp_mint", pool_state.as_ref()], program);
+    let (observation, _) = Pubkey::find_program_address(&[b"observation", pool_state.as_ref()], program);
+    let (token_vault, quote_vault) = raydium_pool_vaults(&token_mint, &quote_mint);
+    let (vault_0, vault_1) = if quote_first { (quote_vault, token_vault) } else { (token_vault, quote_vault) };
+
+    let mut data = RAYDIUM_INITIALIZE_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&amount_0.to_le_bytes());
+    data.extend_from_slice(&amount_1.to_le_bytes());
+    data.extend_from_slice(&0u64.to_le_bytes()); // open_time: open immediately
+
+    Instruction {
+        program_id: *program,
+        accounts: vec![
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(amm_config, false),
+            AccountMeta::new_readonly(raydium_vault_authority(), false),
+            AccountMeta::new(pool_state, false),
+            AccountMeta::new_readonly(mint_0, false),
+            AccountMeta::new_readonly(mint_1, false),
+            AccountMeta::new(lp_mint, false),
+            AccountMeta::new(account_0, false),
+            AccountMeta::new(account_1, false),
+            AccountMeta::new(get_associated_token_address(&payer, &lp_mint), false),
+            AccountMeta::new(vault_0, false),
+            AccountMeta::new(vault_1, false),
+            AccountMeta::new(RAYDIUM_CREATE_POOL_FEE, false),
+            AccountMeta::new(observation, false),
+            AccountMeta::new_readonly(spl_token::ID, false),
+            AccountMeta::new_readonly(spl_token::ID, false),
+            AccountMeta::new_readonly(spl_token::ID, false),
+            AccountMeta::new_readonly(anchor_spl::associated_token::ID, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(sysvar::rent::ID, false),
+        ],
+        data,
+    }
+}
+
+fn log(level: &str, event: &str, fields: Value) {
+    let ts = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let mut line = json!({ "ts": ts, "level": level, "event": event });
+    if let (Some(line), Value::Object(fields)) = (line.as_object_mut(), fields) {
+        line.extend(fields);
+    }
+    println!("{line}");
+}
+
+fn expand_home(path: &str) -> String {
+    match (path.strip_prefix("~/"), std::env::var("HOME")) {
+        (Some(rest), Ok(home)) => format!("{home}/{rest}"),
+        _ => path.to_string(),
+    }
+}
+
+/// Anchor custom error code carried by a failed simulation / transaction, if any.
+fn program_error_code(err: &ClientError) -> Option<u32> {
+    let text = format!("{err:?}");
+    let hex = text.split("custom program error: 0x").nth(1)?;
+    let digits: String = hex.chars().take_while(|c| c.is_ascii_hexdigit()).collect();
+    u32::from_str_radix(&digits, 16).ok()
+}
+
+fn error_code(e: VestigeError) -> u32 {
+    anchor_client::anchor_lang::error::ERROR_CODE_OFFSET + e as u32
+}
+
+/// Program errors meaning "someone else already did it" or "not yet" — not worth retrying.
+fn is_benign(code: u32) -> bool {
+    [
+        VestigeError::AlreadyGraduated,
+        VestigeError::GraduationConditionsNotMet,
+        VestigeError::PoolAlreadyCreated,
+        VestigeError::InvalidStateTransition,
+        VestigeError::AllMilestonesUnlocked,
+        VestigeError::MilestoneNotYetUnlocked,
+        VestigeError::NotGraduated,
+        VestigeError::MilestoneConditionNotMet,
+    ]
+    .into_iter()
+    .any(|e| error_code(e) == code)
+}
+
+struct Keeper {
+    program: Program<Rc<Keypair>>,
+    args: Args,
+    /// Last submission per (launch, crank) — suppresses duplicate sends while the
+    /// previous transaction is still landing.
+    submitted: HashMap<(Pubkey, Crank), Instant>,
+}
+
+impl Keeper {
+    /// Cluster time from the latest confirmed block, falling back to wall clock.
+    fn chain_time(&self) -> i64 {
+        let rpc = self.program.rpc();
+        rpc.get_slot()
+            .and_then(|slot| rpc.get_block_time(slot))
+            .unwrap_or_else(|_| {
+                SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs() as i64).unwrap_or(0)
+            })
+    }
+
+    fn poll(&mut self) -> Result<()> {
+        let now = self.chain_time();
+        let mut launches = Vec::new();
+        for entry in self.program.accounts_lazy::<Launch>(vec![])? {
+            match entry {
+                Ok(launch) => launches.push(launch),
+                Err(err) => log("warn", "launch_decode_failed", json!({ "error": err.to_string() })),
+            }
+        }
+
+        let resubmit = Duration::from_secs(self.args.resubmit_secs);
+        self.submitted.retain(|_, at| at.elapsed() < resubmit);
+
+        let mut due = 0;
+        for (key, launch) in &launches {
+            let Some(crank) = due_crank(launch, now) else { continue };
+            due += 1;
+
+            if self.submitted.contains_key(&(*key, crank)) {
+                log("debug", "crank_pending", json!({ "launch": key.to_string(), "crank": crank.name() }));
+                continue;
+            }
+            self.run(*key, launch, crank);
+        }
+
+        log("info", "poll", json!({ "launches": launches.len(), "due": due, "chain_time": now }));
+        Ok(())
+    }
+
+    /// Submit a crank with exponential backoff on transient failures.
+    fn run(&mut self, launch: Pubkey, state: &Launch, crank: Crank) {
+        let mut backoff = Duration::from_millis(500);
+        for attempt in 1..=self.args.max_retries {
+            match self.send(launch, state, crank) {
+                Ok(sig) => {
+                    self.submitted.insert((launch, crank), Instant::now());
+                    log(
+                        "info",
+                        "crank_confirmed",
+                        json!({
+                            "launch": launch.to_string(),
+                            "crank": crank.name(),
+                            "signature": sig.to_string(),
+                            "attempt": attempt,
+                        }),
+                    );
+                    return;
+                }
+                Err(err) => {
+                    if let Some(code) = program_error_code(&err).filter(|c| is_benign(*c)) {
+                        self.submitted.insert((launch, crank), Instant::now());
+                        log(
+                            "info",
+                            "crank_skipped",
+                            json!({ "launch": launch.to_string(), "crank": crank.name(), "error_code": code }),
+                        );
+                        return;
+                    }
+                    log(
+                        "warn",
+                        "crank_failed",
+                        json!({
+                            "launch": launch.to_string(),
+                            "crank": crank.name(),
+                            "attempt": attempt,
+                            "error": err.to_string(),
+                        }),
+                    );
+                    if attempt < self.args.max_retries {
+                        thread::sleep(backoff);
+                        backoff *= 2;
+                    }
+                }
+            }
+        }
+        log("error", "crank_gave_up", json!({ "launch": launch.to_string(), "crank": crank.name() }));
+    }
+
+    /// Remaining accounts for the condition on the milestone about to be advanced.
+    /// Holders are taken from the mint's largest accounts (at most 20 from the RPC).
+    /// The launch's token vault and the pool vault are usually among them and never
+    /// count, which is why `MAX_MILESTONE_HOLDERS` is 18.
+    fn condition_accounts(&self, state: &Launch) -> std::result::Result<Vec<AccountMeta>, ClientError> {
+        let condition = state
+            .fee_vest_steps()
+            .get(state.milestones_unlocked as usize)
+            .map(|step| step.condition)
+            .unwrap_or_default();
+        match condition {
+            MilestoneCondition::None => Ok(vec![]),
+            MilestoneCondition::MinPoolPriceBps(_) => {
+                let (token_vault, quote_vault) = raydium_pool_vaults(&state.token_mint, &state.pool_quote_mint());
+                Ok(vec![
+                    AccountMeta::new_readonly(token_vault, false),
+                    AccountMeta::new_readonly(quote_vault, false),
+                ])
+            }
+            MilestoneCondition::MinHolders(_) => {
+                let largest = self
+                    .program
+                    .rpc()
+                    .get_token_largest_accounts(&state.token_mint)
+                    .map_err(|e| ClientError::SolanaClientError(Box::new(e)))?;
+                Ok(largest
+                    .iter()
+                    .filter_map(|balance| Pubkey::from_str(&balance.address).ok())
+                    .map(|address| AccountMeta::new_readonly(address, false))
+                    .collect())
+            }
+        }
+    }
+
+    /// `graduate_to_dex` and the Raydium pool creation that must follow it in the same
+    /// transaction, so the released liquidity never rests in the keeper's wallet.
+    /// SOL is wrapped into the keeper's WSOL account between the two.
+    fn listing_instructions(&self, launch: Pubkey, state: &Launch) -> std::result::Result<Vec<Instruction>, ClientError> {
+        let payer = self.program.payer();
+        let rpc = self.program.rpc();
+        let vault = Pubkey::find_program_address(&[VAULT_SEED, launch.as_ref()], &vestige::ID).0;
+        let vault_balance = if state.uses_quote_mint() {
+            let balance = rpc.get_token_account_balance(&vault).map_err(|e| ClientError::SolanaClientError(Box::new(e)))?;
+            balance.amount.parse().unwrap_or(0)
+        } else {
+            let lamports = rpc.get_balance(&vault).map_err(|e| ClientError::SolanaClientError(Box::new(e)))?;
+            let rent = rpc
+                .get_minimum_balance_for_rent_exemption(0)
+                .map_err(|e| ClientError::SolanaClientError(Box::new(e)))?;
+            lamports.saturating_sub(rent)
+        };
+        let (quote_for_pool, tokens_for_pool) = pool_amounts(state, vault_balance)?;
+
+        let quote_mint = state.pool_quote_mint();
+        let payer_token = get_associated_token_address(&payer, &state.token_mint);
+        let payer_quote = get_associated_token_address(&payer, &quote_mint);
+        let token_vault = get_associated_token_address(&launch, &state.token_mint);
+        let treasury_quote = (state.uses_quote_mint() && state.graduation_fee_bps > 0)
+            .then(|| get_associated_token_address(&PROTOCOL_TREASURY, &quote_mint));
+
+        let mut instructions = vec![
+            ComputeBudgetInstruction::set_compute_unit_limit(LISTING_COMPUTE_UNITS),
+            create_associated_token_account_idempotent(&payer, &payer, &state.token_mint, &spl_token::ID),
+            create_associated_token_account_idempotent(&payer, &payer, &quote_mint, &spl_token::ID),
+            Instruction {
+                program_id: vestige::ID,
+                accounts: vestige::accounts::GraduateToDex {
+                    launch,
+                    vault,
+                    token_vault,
+                    payer,
+                    payer_token_account: payer_token,
+                    token_program: spl_token::ID,
+                    system_program: system_program::ID,
+                    payer_quote_account: state.uses_quote_mint().then_some(payer_quote),
+                    protocol_treasury: PROTOCOL_TREASURY,
+                    treasury_quote_account: treasury_quote,
+                }
+                .to_account_metas(None),
+                data: vestige::instruction::GraduateToDex {}.data(),
+            },
+        ];
+        if !state.uses_quote_mint() {
+            instructions.push(solana_system_interface::instruction::transfer(&payer, &payer_quote, quote_for_pool));
+            instructions.push(
+                spl_token::instruction::sync_native(&spl_token::ID, &payer_quote).map_err(ClientError::ProgramError)?,
+            );
+        }
+        instructions.push(raydium_initialize(
+            payer,
+            state.token_mint,
+            quote_mint,
+            payer_token,
+            payer_quote,
+            tokens_for_pool,
+            quote_for_pool,
+        ));
+        Ok(instructions)
+    }
+
+    fn send(&self, launch: Pubkey, state: &Launch, crank: Crank) -> std::result::Result<Signature, ClientError> {
+        let authority = self.program.payer();
+        match crank {
+            Crank::Graduate => self
+                .program
+                .request()
+                // Quote-mint launches: no quote account is passed, so the bounty is skipped
+                .accounts(vestige::accounts::Graduate {
+                    launch,
+                    vault: Pubkey::find_program_address(&[VAULT_SEED, launch.as_ref()], &vestige::ID).0,
+                    authority,
+                    authority_quote_account: None,
+                    token_program: None,
+                })
+                .args(vestige::instruction::Graduate {})
+                .send(),
+            Crank::GraduateToDex => self
+                .listing_instructions(launch, state)?
+                .into_iter()
+                .fold(self.program.request(), |request, ix| request.instruction(ix))
+                .send(),
+            Crank::AdvanceMilestone { .. } => self
+                .program
+                .request()
+                .accounts(vestige::accounts::AdvanceMilestone { launch, authority })
+                .accounts(self.condition_accounts(state)?)
+                .args(vestige::instruction::AdvanceMilestone {})
+                .send(),
+        }
+    }
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    let cluster = Cluster::from_str(&args.cluster)?;
+    let payer = read_keypair_file(expand_home(&args.keypair))
+        .map_err(|e| anyhow!("reading keypair {}: {e}", args.keypair))?;
+    log("info", "start", json!({ "cluster": cluster.url(), "payer": payer.pubkey().to_string() }));
+
+    let client = Client::new_with_options(cluster, Rc::new(payer), CommitmentConfig::confirmed());
+    let program = client.program(vestige::ID)?;
+    let mut keeper = Keeper { program, args, submitted: HashMap::new() };
+
+    loop {
+        if let Err(err) = keeper.poll() {
+            log("error", "poll_failed", json!({ "error": err.to_string() }));
+        }
+        if keeper.args.once {
+            return Ok(());
+        }
+        thread::sleep(Duration::from_secs(keeper.args.poll_secs));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOL: u64 = 1_000_000_000;
+
+    fn launch(state: LaunchState, total_sol_collected: u64) -> Launch {
+        Launch {
+            state,
+            total_sol_collected,
+            graduation_target: 10 * SOL,
+            end_time: 1_000,
+            lp_reserve: 500,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn due_crank_closes_the_curve_then_lists() {
+        // At the target, or past the end time short of it (the launch fails)
+        assert_eq!(due_crank(&launch(LaunchState::Active, 10 * SOL), 0), Some(Crank::Graduate));
+        assert_eq!(due_crank(&launch(LaunchState::Active, SOL), 1_001), Some(Crank::Graduate));
+        assert_eq!(due_crank(&launch(LaunchState::Active, SOL), 1_000), None);
+        // Graduated by the buy that reached the target, or by `graduate`
+        assert_eq!(due_crank(&launch(LaunchState::Graduating, 10 * SOL), 0), Some(Crank::GraduateToDex));
+        assert_eq!(due_crank(&launch(LaunchState::Graduated, 10 * SOL), 0), None);
+        assert_eq!(due_crank(&launch(LaunchState::Failed, SOL), 2_000), None);
+    }
+
+    #[test]
+    fn pool_amounts_pay_the_owed_bounty_first() {
+        let mut state = launch(LaunchState::Graduating, 10 * SOL);
+        state.graduation_bounty_bps = 50;
+        state.graduation_fee_bps = 100;
+        let vault = 10 * SOL;
+        assert_eq!(pool_amounts(&state, vault).unwrap(), (vault - vault / 200 - vault / 100, 500));
+        // Already paid by `graduate`: the vault no longer holds it
+        state.graduation_bounty_paid = vault / 200;
+        assert_eq!(
+            pool_amounts(&state, vault - vault / 200).unwrap(),
+            (vault - vault / 200 - vault / 100, 500)
+        );
+    }
+
+    #[test]
+    fn stale_cranks_are_benign() {
+        for e in [VestigeError::PoolAlreadyCreated, VestigeError::InvalidStateTransition] {
+            assert!(is_benign(error_code(e)));
+        }
+        assert!(!is_benign(error_code(VestigeError::Overflow)));
+    }
+}
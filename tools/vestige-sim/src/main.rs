@@ -0,0 +1,202 @@
+//! vestige-sim — Monte Carlo simulator for launch parameters.
+//!
+//! Runs many randomized buyer/seller flows against the program's own curve,
+//! weight and fee math and reports price paths, bonus distribution, the chance
+//! of exhausting supply or the bonus pool, and effective entry price per cohort.
+//!
+//! Token amounts are given in whole tokens (9 decimals) and SOL amounts in SOL.
+
+mod sim;
+
+use anyhow::{bail, Result};
+use clap::Parser;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use sim::{FlowParams, LaunchParams, Outcome, RunResult, COHORTS, DECILES};
+use vestige::TOKEN_PRECISION;
+
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+
+#[derive(Parser, Debug)]
+#[command(name = "vestige-sim", about = "Monte Carlo simulation of Vestige launch parameters")]
+struct Args {
+    /// Tradeable supply on the curve (whole tokens).
+    #[arg(long, default_value_t = 1_000_000)]
+    token_supply: u64,
+    /// Bonus pool (whole tokens). Must cover token_supply * (r_best - 1).
+    #[arg(long, default_value_t = 2_000_000)]
+    bonus_pool: u64,
+    /// Tokens reserved for the DEX pool (whole tokens).
+    #[arg(long, default_value_t = 200_000)]
+    lp_reserve: u64,
+    #[arg(long, default_value_t = 3)]
+    r_best: u64,
+    #[arg(long, default_value_t = 1)]
+    r_min: u64,
+    /// Graduation target in SOL.
+    #[arg(long, default_value_t = 10.0)]
+    graduation_target: f64,
+
+    /// Number of simulated launches.
+    #[arg(long, default_value_t = 1_000)]
+    runs: u32,
+    /// RNG seed, for reproducible reports.
+    #[arg(long, default_value_t = 42)]
+    seed: u64,
+    /// Trades per run before it is counted as stalled.
+    #[arg(long, default_value_t = 5_000)]
+    max_trades: u32,
+    /// Probability that a trade is a sell.
+    #[arg(long, default_value_t = 0.2)]
+    sell_prob: f64,
+    /// Probability that a buy comes from a wallet that already bought.
+    #[arg(long, default_value_t = 0.3)]
+    repeat_buyer_prob: f64,
+    /// Mean buy size in SOL.
+    #[arg(long, default_value_t = 0.25)]
+    mean_buy: f64,
+    /// Log-normal sigma of buy sizes (spread of whales vs minnows).
+    #[arg(long, default_value_t = 1.0)]
+    buy_sigma: f64,
+}
+
+fn tokens(whole: u64) -> u64 {
+    whole.saturating_mul(TOKEN_PRECISION as u64)
+}
+
+fn lamports(sol: f64) -> u64 {
+    (sol * LAMPORTS_PER_SOL) as u64
+}
+
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return f64::NAN;
+    }
+    let idx = ((sorted.len() - 1) as f64 * p).round() as usize;
+    sorted[idx]
+}
+
+fn summary(mut values: Vec<f64>) -> (f64, f64, f64, f64) {
+    values.sort_by(|a, b| a.total_cmp(b));
+    let mean = values.iter().sum::<f64>() / values.len().max(1) as f64;
+    (percentile(&values, 0.05), mean, percentile(&values, 0.5), percentile(&values, 0.95))
+}
+
+/// Share of all bonus held by the top 10% of wallets (by bonus).
+fn top_decile_share(wallet_bonus: &[u64]) -> Option<f64> {
+    let mut bonus: Vec<u64> = wallet_bonus.iter().copied().filter(|b| *b > 0).collect();
+    let total: u128 = bonus.iter().map(|b| *b as u128).sum();
+    if total == 0 {
+        return None;
+    }
+    bonus.sort_unstable_by(|a, b| b.cmp(a));
+    let top = bonus.len().div_ceil(10);
+    let top_sum: u128 = bonus[..top].iter().map(|b| *b as u128).sum();
+    Some(top_sum as f64 / total as f64)
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    if !(0.0..1.0).contains(&args.sell_prob) || !(0.0..=1.0).contains(&args.repeat_buyer_prob) {
+        bail!("probabilities must be within [0, 1)");
+    }
+    if args.mean_buy <= 0.0 || args.buy_sigma <= 0.0 {
+        bail!("--mean-buy and --buy-sigma must be positive");
+    }
+
+    let params = LaunchParams {
+        token_supply: tokens(args.token_supply),
+        bonus_pool: tokens(args.bonus_pool),
+        lp_reserve: tokens(args.lp_reserve),
+        r_best: args.r_best,
+        r_min: args.r_min,
+        graduation_target: lamports(args.graduation_target),
+    };
+    let flow = FlowParams {
+        max_trades: args.max_trades,
+        sell_prob: args.sell_prob,
+        repeat_buyer_prob: args.repeat_buyer_prob,
+        mean_buy: args.mean_buy * LAMPORTS_PER_SOL,
+        buy_sigma: args.buy_sigma,
+    };
+    let launch = sim::new_launch(&params).map_err(|e| anyhow::anyhow!("invalid launch parameters: {e}"))?;
+
+    let mut rng = StdRng::seed_from_u64(args.seed);
+    let mut results: Vec<RunResult> = Vec::with_capacity(args.runs as usize);
+    for _ in 0..args.runs {
+        results.push(sim::run(&params, &flow, &mut rng).map_err(|e| anyhow::anyhow!("simulation failed: {e}"))?);
+    }
+
+    let runs = results.len() as f64;
+    let sol = |lamports: f64| lamports / LAMPORTS_PER_SOL;
+
+    println!("Launch parameters");
+    println!(
+        "  supply {} | bonus pool {} | lp reserve {} | weight {} -> {} | target {} SOL",
+        args.token_supply, args.bonus_pool, args.lp_reserve, args.r_best, args.r_min, args.graduation_target
+    );
+    println!("  price {:.9} SOL (start) -> {:.9} SOL (DEX listing) per token", sol(launch.p_max as f64), sol(launch.p_min as f64));
+    println!("  {} runs, seed {}, max {} trades, mean buy {} SOL", args.runs, args.seed, args.max_trades, args.mean_buy);
+
+    println!("\nOutcomes");
+    for (label, outcome) in [
+        ("graduated", Outcome::Graduated),
+        ("supply exhausted (TokenSupplyExceeded)", Outcome::SupplyExhausted),
+        ("stalled (max trades)", Outcome::Stalled),
+    ] {
+        let n = results.iter().filter(|r| r.outcome == outcome).count() as f64;
+        println!("  {label:<40} {:>6.2}%", 100.0 * n / runs);
+    }
+    let capped = results.iter().filter(|r| r.bonus_exhausted).count() as f64;
+    println!("  {:<40} {:>6.2}%", "bonus pool exhausted (bonus capped)", 100.0 * capped / runs);
+    let (p5, mean, p50, p95) = summary(results.iter().map(|r| r.trades as f64).collect());
+    println!("  trades per run: p5 {p5:.0} | mean {mean:.1} | p50 {p50:.0} | p95 {p95:.0}");
+
+    println!("\nPrice path (curve price in SOL/token when graduation progress is first reached)");
+    println!("  {:>8} {:>7} {:>14} {:>14} {:>14}", "progress", "runs", "p5", "mean", "p95");
+    for k in 0..DECILES {
+        let samples: Vec<f64> = results.iter().filter_map(|r| r.price_at_decile[k]).map(|p| sol(p as f64)).collect();
+        if samples.is_empty() {
+            println!("  {:>7}% {:>7}", k * 10, 0);
+            continue;
+        }
+        let n = samples.len();
+        let (p5, mean, _, p95) = summary(samples);
+        println!("  {:>7}% {:>7} {:>14.9} {:>14.9} {:>14.9}", k * 10, n, p5, mean, p95);
+    }
+
+    println!("\nBonus distribution");
+    let reserved: Vec<f64> = results
+        .iter()
+        .map(|r| 100.0 * r.bonus_reserved as f64 / params.bonus_pool as f64)
+        .collect();
+    let (p5, mean, p50, p95) = summary(reserved);
+    println!("  pool reserved at end: p5 {p5:.1}% | mean {mean:.1}% | p50 {p50:.1}% | p95 {p95:.1}%");
+    let top: Vec<f64> = results.iter().filter_map(|r| top_decile_share(&r.wallet_bonus)).map(|s| 100.0 * s).collect();
+    if !top.is_empty() {
+        let (p5, mean, _, p95) = summary(top);
+        println!("  bonus held by top 10% of wallets: p5 {p5:.1}% | mean {mean:.1}% | p95 {p95:.1}%");
+    }
+
+    println!("\nEffective entry price per cohort (SOL spent / (base + bonus) tokens still held)");
+    println!("  listing price p_min = {:.9} SOL/token", sol(launch.p_min as f64));
+    for c in 0..COHORTS {
+        let (spent, held) = results.iter().fold((0u128, 0u128), |(s, h), r| {
+            let t = &r.cohorts[c];
+            (s + t.sol_spent, h + t.base_tokens + t.bonus_tokens)
+        });
+        let label = format!("{}-{}%", c * 100 / COHORTS, (c + 1) * 100 / COHORTS);
+        if held == 0 {
+            println!("  {label:>8}: no holdings");
+            continue;
+        }
+        let price = spent as f64 * TOKEN_PRECISION as f64 / held as f64;
+        println!(
+            "  {label:>8}: {:.9} SOL/token ({:.2}x listing)",
+            sol(price),
+            price / launch.p_min as f64
+        );
+    }
+
+    Ok(())
+}
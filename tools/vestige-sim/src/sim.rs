@@ -0,0 +1,239 @@
+//! Single-run engine. All pricing, fee, bonus and state-transition math is the
+//! program's own (`compute_buy`, `compute_sell`, `Launch::record_*`,
+//! `UserPosition::record_*`), so a simulated path is a path the program would take.
+
+use anchor_lang::error::Error;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use rand_distr::{Distribution, LogNormal};
+use vestige::{
+    compute_buy, compute_sell, derive_prices, get_curve_price, get_graduation_progress_bps,
+    max_bonus_liability, Launch, LaunchState, UserPosition, VestigeError, BPS_DENOMINATOR,
+    MIN_INITIAL_BUY,
+};
+
+/// Entry cohorts by graduation progress at the time of the buy: 0–25%, 25–50%, 50–75%, 75–100%.
+pub const COHORTS: usize = 4;
+/// Price-path sample points: every 10% of graduation progress, 0% through 100%.
+pub const DECILES: usize = 11;
+
+#[derive(Debug, Clone, Copy)]
+pub struct LaunchParams {
+    pub token_supply: u64,
+    pub bonus_pool: u64,
+    pub lp_reserve: u64,
+    pub r_best: u64,
+    pub r_min: u64,
+    pub graduation_target: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct FlowParams {
+    /// Trades per run before it is counted as stalled.
+    pub max_trades: u32,
+    /// Probability that a trade is a sell (when anyone holds tokens).
+    pub sell_prob: f64,
+    /// Probability that a buy comes from an existing wallet rather than a new one.
+    pub repeat_buyer_prob: f64,
+    /// Mean buy size in lamports (log-normally distributed).
+    pub mean_buy: f64,
+    /// Log-normal sigma of buy sizes.
+    pub buy_sigma: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Graduated,
+    SupplyExhausted,
+    Stalled,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CohortTotals {
+    /// Lamports spent on tokens still held at the end of the run.
+    pub sol_spent: u128,
+    pub base_tokens: u128,
+    pub bonus_tokens: u128,
+}
+
+pub struct RunResult {
+    pub outcome: Outcome,
+    pub trades: u32,
+    /// Whether any buy had its bonus cut because the pool ran out.
+    pub bonus_exhausted: bool,
+    pub price_at_decile: [Option<u64>; DECILES],
+    pub bonus_reserved: u64,
+    /// Final bonus entitlement per wallet.
+    pub wallet_bonus: Vec<u64>,
+    pub cohorts: [CohortTotals; COHORTS],
+}
+
+struct Wallet {
+    position: UserPosition,
+    cohorts: [CohortTotals; COHORTS],
+}
+
+impl Wallet {
+    fn new() -> Self {
+        Wallet { position: UserPosition::default(), cohorts: [CohortTotals::default(); COHORTS] }
+    }
+}
+
+/// Build an in-memory launch, applying the same parameter checks as `initialize_launch`.
+pub fn new_launch(params: &LaunchParams) -> anchor_lang::Result<Launch> {
+    anchor_lang::require!(
+        params.bonus_pool >= max_bonus_liability(params.token_supply, params.r_best)?,
+        VestigeError::BonusPoolTooSmall
+    );
+    let (p_max, p_min) = derive_prices(params.graduation_target, params.lp_reserve, params.r_best)?;
+    Ok(Launch {
+        token_supply: params.token_supply,
+        bonus_pool: params.bonus_pool,
+        lp_reserve: params.lp_reserve,
+        r_best: params.r_best,
+        r_min: params.r_min,
+        graduation_target: params.graduation_target,
+        p_max,
+        p_min,
+        end_time: i64::MAX,
+        duration: i64::MAX,
+        ..Default::default()
+    })
+}
+
+fn is(err: &Error, code: VestigeError) -> bool {
+    match err {
+        Error::AnchorError(e) => e.error_code_number == anchor_lang::error::ERROR_CODE_OFFSET + code as u32,
+        Error::ProgramError(_) => false,
+    }
+}
+
+fn cohort_of(progress_bps: u64) -> usize {
+    ((progress_bps as usize) * COHORTS / (BPS_DENOMINATOR as usize + 1)).min(COHORTS - 1)
+}
+
+pub fn run<R: Rng>(params: &LaunchParams, flow: &FlowParams, rng: &mut R) -> anchor_lang::Result<RunResult> {
+    let mut launch = new_launch(params)?;
+    let sizes = LogNormal::new(flow.mean_buy.ln() - flow.buy_sigma.powi(2) / 2.0, flow.buy_sigma)
+        .expect("valid log-normal parameters");
+
+    let mut wallets: Vec<Wallet> = vec![Wallet::new()]; // wallet 0 is the creator
+    let mut price_at_decile = [None; DECILES];
+    price_at_decile[0] = Some(launch.p_max);
+
+    let mut trades = 0;
+    let mut bonus_exhausted = false;
+    let mut outcome = Outcome::Stalled;
+    let mut next = Some((0usize, MIN_INITIAL_BUY)); // creator's activation buy
+
+    while trades < flow.max_trades {
+        if launch.total_sol_collected >= launch.graduation_target {
+            outcome = Outcome::Graduated;
+            break;
+        }
+
+        let holders: Vec<usize> = (1..wallets.len())
+            .filter(|&i| wallets[i].position.total_base_tokens > 0)
+            .collect();
+
+        let (wallet, sol_amount) = match next.take() {
+            Some(buy) => buy,
+            None if !holders.is_empty() && rng.gen_bool(flow.sell_prob) => {
+                let i = *holders.choose(rng).unwrap();
+                let held = wallets[i].position.total_base_tokens;
+                let amount = ((held as f64) * rng.gen_range(0.1..=1.0)) as u64;
+                if sell(&mut launch, &mut wallets[i], amount.clamp(1, held)).is_ok() {
+                    trades += 1;
+                }
+                sample_price(&launch, &mut price_at_decile);
+                continue;
+            }
+            None => {
+                let wallet = if wallets.len() > 1 && rng.gen_bool(flow.repeat_buyer_prob) {
+                    rng.gen_range(1..wallets.len())
+                } else {
+                    wallets.push(Wallet::new());
+                    wallets.len() - 1
+                };
+                (wallet, sizes.sample(rng).max(1.0) as u64)
+            }
+        };
+
+        match buy(&mut launch, &mut wallets[wallet], sol_amount) {
+            Ok(shortfall) => {
+                trades += 1;
+                bonus_exhausted |= shortfall > 0;
+            }
+            Err(e) if is(&e, VestigeError::TokenSupplyExceeded) => {
+                outcome = Outcome::SupplyExhausted;
+                break;
+            }
+            Err(e) if is(&e, VestigeError::ZeroBaseTokens) => {}
+            Err(e) => return Err(e),
+        }
+        sample_price(&launch, &mut price_at_decile);
+    }
+
+    let mut cohorts = [CohortTotals::default(); COHORTS];
+    for w in &wallets {
+        for (total, c) in cohorts.iter_mut().zip(w.cohorts.iter()) {
+            total.sol_spent += c.sol_spent;
+            total.base_tokens += c.base_tokens;
+            total.bonus_tokens += c.bonus_tokens;
+        }
+    }
+
+    Ok(RunResult {
+        outcome,
+        trades,
+        bonus_exhausted,
+        price_at_decile,
+        bonus_reserved: launch.total_bonus_reserved,
+        wallet_bonus: wallets.iter().map(|w| w.position.total_bonus_entitled).collect(),
+        cohorts,
+    })
+}
+
+fn sample_price(launch: &Launch, samples: &mut [Option<u64>; DECILES]) {
+    let progress = get_graduation_progress_bps(launch, launch.total_sol_collected);
+    let price = get_curve_price(launch, launch.total_base_sold);
+    for (k, slot) in samples.iter_mut().enumerate() {
+        if slot.is_none() && progress >= (k as u64) * BPS_DENOMINATOR / 10 {
+            *slot = Some(price);
+        }
+    }
+}
+
+/// Returns the bonus shortfall (bonus cut because the pool ran out).
+fn buy(launch: &mut Launch, wallet: &mut Wallet, sol_amount: u64) -> anchor_lang::Result<u64> {
+    let cohort = cohort_of(get_graduation_progress_bps(launch, launch.total_sol_collected));
+    let quote = compute_buy(launch, sol_amount, 0)?;
+    let is_new = wallet.position.total_sol_spent == 0 && wallet.position.total_base_tokens == 0;
+    wallet.position.record_buy(&quote)?;
+    if launch.state == LaunchState::Pending {
+        launch.set_state(LaunchState::Active, 0)?; // creator's activation buy
+    }
+    launch.record_buy(&quote, is_new)?;
+
+    let c = &mut wallet.cohorts[cohort];
+    c.sol_spent += quote.sol_amount as u128;
+    c.base_tokens += quote.base_tokens as u128;
+    c.bonus_tokens += quote.bonus as u128;
+    Ok(quote.bonus_shortfall)
+}
+
+fn sell(launch: &mut Launch, wallet: &mut Wallet, token_amount: u64) -> anchor_lang::Result<()> {
+    let held = wallet.position.total_base_tokens as u128;
+    let quote = compute_sell(launch, token_amount)?;
+    let bonus_reduction = wallet.position.record_sell(token_amount)?;
+    launch.record_sell(&quote, bonus_reduction)?;
+
+    // Mirror the program's proportional reduction across the wallet's entry cohorts.
+    let remaining = held - token_amount as u128;
+    for c in wallet.cohorts.iter_mut() {
+        c.sol_spent = c.sol_spent * remaining / held;
+        c.base_tokens = c.base_tokens * remaining / held;
+        c.bonus_tokens = c.bonus_tokens * remaining / held;
+    }
+    Ok(())
+}